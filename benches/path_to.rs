@@ -0,0 +1,43 @@
+// Benchmarks for the nav graph path-finding, comparing the binary heap A* in `TileGraph::path_to` with the original
+// implementation that scans every node to find the next one to visit.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use nea_project::level::TileGraph;
+use nea_project::tile::{Tile, TileSize};
+
+/// An open nav graph of `size` by `size` tiles, with a wall down the middle that has a gap at the bottom
+fn create_graph(size : i32) -> TileGraph {
+    let mut graph = TileGraph::create(TileSize::SMALL);
+    for x in 0..size {
+        for y in 0..size {
+            if x != size / 2 || y == size - 1 {
+                graph.append(Tile::create_nav(), (x, y), vec![]);
+            }
+        }
+    }
+    graph.build_connections();
+    graph
+}
+
+fn path_to_benchmark(c : &mut Criterion) {
+    let mut group = c.benchmark_group("path_to");
+
+    for size in [16, 32, 64] {
+        let mut graph = create_graph(size);
+        // from the top left corner to the top right corner, around the wall
+        let target = ((size - 1) * 16, 0);
+
+        group.bench_with_input(BenchmarkId::new("a_star", size), &size, |b, _| {
+            b.iter(|| graph.path_to(black_box(0), black_box(0), target.0, target.1))
+        });
+
+        group.bench_with_input(BenchmarkId::new("linear_scan", size), &size, |b, _| {
+            b.iter(|| graph.path_to_linear_scan(black_box(0), black_box(0), target.0, target.1))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, path_to_benchmark);
+criterion_main!(benches);
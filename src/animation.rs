@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use log::warn;
+use sdl2::rect::Rect;
+use crate::entity_definition::EntityDefinition;
+use crate::game::Game;
+use crate::render::AssetData;
+use crate::resource_location::ResourceLocation;
+
+/// A single frame of an [`Animation`], and how long it is shown for in seconds
+#[derive(Clone)]
+pub struct Frame {
+    pub asset_data : AssetData,
+    pub duration : f32
+}
+
+/// A sequence of frames from a texture, loaded from a `.anim.json` file in `assets/<namespace>/`. The file format is:
+/// ```json
+/// {
+///   "texture" : "game:entity/explosion/explosion.png",
+///   "origin" : { "x" : 32, "y" : 32 },
+///   "frame_time" : 0.125,
+///   "looping" : false,
+///   "on_complete" : "despawn",
+///   "frames" : [
+///     { "x" : 0, "y" : 0, "w" : 64, "h" : 64 },
+///     { "x" : 0, "y" : 64, "w" : 64, "h" : 64, "duration" : 0.25 }
+///   ]
+/// }
+/// ```
+/// Each frame is shown for `frame_time` seconds unless it has its own `duration`. `looping` defaults to false, in which case
+/// the animation stays on its last frame once it is over. `on_complete` is optional, and is the event handed to the entity
+/// playing the animation each time it finishes, see [`Animator::tick`].
+#[derive(Clone)]
+pub struct Animation {
+    pub resource_location : ResourceLocation,
+    pub frames : Vec<Frame>,
+    pub looping : bool,
+    pub on_complete : Option<String>
+}
+
+impl Animation {
+
+    /// Parse an animation from the contents of its JSON file, given the resource location it is registered under
+    pub fn parse(resource_location : ResourceLocation, json : &str) -> Result<Self, String> {
+        if !gjson::valid(json) {
+            return Err(format!("Animation {} is not valid JSON!", resource_location.to_string()))
+        }
+
+        for field in ["texture", "frames"] {
+            if !gjson::get(json, field).exists() {
+                return Err(format!("Animation {} is missing \"{}\"!", resource_location.to_string(), field))
+            }
+        }
+
+        let texture = ResourceLocation::parse(gjson::get(json, "texture").to_string());
+        let origin = (gjson::get(json, "origin.x").i32(), gjson::get(json, "origin.y").i32());
+        let frame_time = gjson::get(json, "frame_time");
+        let frame_time = if frame_time.exists() { frame_time.f32() } else { 0.1 };
+
+        let frames = gjson::get(json, "frames").array().iter().map(|frame| {
+            let duration = frame.get("duration");
+            Frame {
+                asset_data : AssetData {
+                    uv : Some(Rect::new(frame.get("x").i32(), frame.get("y").i32(), frame.get("w").u32(), frame.get("h").u32())),
+                    origin,
+                    resource_location : texture.clone()
+                },
+                duration : if duration.exists() { duration.f32() } else { frame_time }
+            }
+        }).collect::<Vec<_>>();
+
+        if frames.is_empty() {
+            return Err(format!("Animation {} has no frames!", resource_location.to_string()))
+        }
+
+        let on_complete = gjson::get(json, "on_complete");
+
+        Ok(Self {
+            resource_location,
+            frames,
+            looping : gjson::get(json, "looping").bool(),
+            on_complete : if on_complete.exists() { Some(on_complete.to_string()) } else { None }
+        })
+    }
+
+    /// How long it takes to play every frame once, in seconds
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.duration).sum()
+    }
+
+    /// The frame shown a given amount of time after the animation started
+    pub fn frame_at(&self, time : f32) -> &Frame {
+        let duration = self.duration();
+        let mut time = if self.looping && duration > 0.0 { time % duration } else { time };
+        for frame in self.frames.iter() {
+            if time < frame.duration {
+                return frame
+            }
+            time -= frame.duration;
+        }
+        // a one shot animation stays on its last frame
+        self.frames.last().unwrap()
+    }
+}
+
+/// Plays back an entity's animations, each given a name such as `idle` or `walk`. Any entity can use one in
+/// [`Entity::get_asset_data`], and choose which animation is playing in its tick.
+///
+/// [`Entity::get_asset_data`]: crate::entity::Entity::get_asset_data
+pub struct Animator {
+    animations : HashMap<String, Animation>,
+    current : Option<String>,
+    timer : f32,
+    finished : bool
+}
+
+impl Animator {
+
+    /// Create an animator from a list of names and animations
+    pub fn create(animations : HashMap<String, Animation>) -> Self {
+        Self {
+            animations,
+            current : None,
+            timer : 0.0,
+            finished : false
+        }
+    }
+
+    /// Create an animator with the animations listed in an entity's definition, from those loaded into the game. If the
+    /// definition has an `idle` animation it is played straight away
+    pub fn from_definition(game : &Game, definition : &EntityDefinition) -> Self {
+        let mut animations = HashMap::new();
+        for (name, rl) in definition.animations.iter() {
+            match game.animations.get(&rl.to_string()) {
+                Some(animation) => { animations.insert(name.clone(), animation.clone()); }
+                None => { warn!("Animation {} for {} could not be found!", rl.to_string(), definition.resource_location.to_string()) }
+            }
+        }
+
+        let mut animator = Self::create(animations);
+        animator.play("idle");
+        animator
+    }
+
+    /// Whether the animator has an animation with the given name
+    pub fn has(&self, name : &str) -> bool {
+        self.animations.contains_key(name)
+    }
+
+    /// Start playing an animation. Does nothing if it is already playing, but a finished animation is played again from
+    /// the start
+    pub fn play(&mut self, name : &str) {
+        if !self.has(name) || (self.current.as_deref() == Some(name) && !self.finished) {
+            return
+        }
+        self.current = Some(name.to_string());
+        self.timer = 0.0;
+        self.finished = false;
+    }
+
+    /// The name of the animation being played
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    /// Whether a one shot animation has finished, or there is no animation playing
+    pub fn is_finished(&self) -> bool {
+        self.current.is_none() || self.finished
+    }
+
+    /// Advance the current animation. Returns the animation's `on_complete` event if it finished during this tick
+    pub fn tick(&mut self, delta : f32) -> Option<String> {
+        let animation = self.animations.get(self.current.as_ref()?)?;
+        if self.finished {
+            return None
+        }
+
+        let duration = animation.duration();
+        self.timer += delta;
+
+        if self.timer < duration {
+            return None
+        }
+
+        if animation.looping {
+            // only the time into the current loop is needed, so the event is sent once per loop
+            if duration > 0.0 {
+                self.timer %= duration;
+            }
+            animation.on_complete.clone()
+        }
+        else {
+            self.finished = true;
+            animation.on_complete.clone()
+        }
+    }
+
+    /// The asset data of the frame being shown, or None if there is no animation playing
+    pub fn get_asset_data(&self) -> Option<AssetData> {
+        let animation = self.animations.get(self.current.as_ref()?)?;
+        Some(animation.frame_at(self.timer).asset_data.clone())
+    }
+}
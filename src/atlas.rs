@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use image::{imageops, RgbaImage};
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Texture, TextureCreator};
+use sdl2::video::WindowContext;
+use crate::resource_location::ResourceLocation;
+
+/// The width and height of each atlas page
+pub const PAGE_SIZE : u32 = 1024;
+
+/// The gap left between packed images, so that one image never bleeds into the next
+pub const PADDING : u32 = 1;
+
+/// Where an image ended up in the atlas
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AtlasRegion {
+    /// The index of the page the image is on
+    pub page : usize,
+    /// The area of the page the image covers
+    pub rect : Rect
+}
+
+impl AtlasRegion {
+
+    /// Convert a UV inside the original image into a UV on the atlas page. Anything outside of the original image is clipped
+    /// off, the same as SDL2 does for a texture on its own, and None is returned if there is nothing left. No UV is the
+    /// whole image
+    pub fn remap(&self, uv : Option<Rect>) -> Option<Rect> {
+        let uv = match uv {
+            Some(uv) => { uv }
+            None => { return Some(self.rect) }
+        };
+
+        // done by hand rather than with Rect::intersection, so the atlas doesn't need SDL2 to be initialised
+        let left = uv.left().max(0);
+        let top = uv.top().max(0);
+        let right = uv.right().min(self.rect.width() as i32);
+        let bottom = uv.bottom().min(self.rect.height() as i32);
+        if left >= right || top >= bottom {
+            return None
+        }
+        Some(Rect::new(self.rect.x() + left, self.rect.y() + top, (right - left) as u32, (bottom - top) as u32))
+    }
+}
+
+/// The layout of every image in the atlas, built on the CPU before anything is uploaded to the GPU. Small images are packed
+/// onto shared pages in rows, sorted from tallest to shortest, and any image too large to share a page is given one of its
+/// own.
+pub struct AtlasLayout {
+    pub pages : Vec<RgbaImage>,
+    pub regions : HashMap<String, AtlasRegion>
+}
+
+impl AtlasLayout {
+
+    /// Pack a list of images given the size of each page
+    pub fn pack(mut images : Vec<(ResourceLocation, RgbaImage)>, page_size : u32) -> Self {
+        let mut pages = vec![];
+        let mut regions = HashMap::new();
+
+        // tallest first, so each row wastes as little space as possible. The order has to be the same every time
+        images.sort_by(|a, b| b.1.height().cmp(&a.1.height()).then(a.0.to_string().cmp(&b.0.to_string())));
+
+        // the page currently being packed, and how far through it the current row is
+        let mut current : Option<usize> = None;
+        let mut cursor = (0, 0);
+        let mut row_height = 0;
+
+        for (rl, image) in images {
+            let (w, h) = image.dimensions();
+
+            // images larger than a quarter of a page would waste most of it
+            if w > page_size / 4 || h > page_size / 4 {
+                regions.insert(rl.to_string(), AtlasRegion { page : pages.len(), rect : Rect::new(0, 0, w, h) });
+                pages.push(image);
+                continue
+            }
+
+            // start a new row when this one is full
+            if cursor.0 + w > page_size {
+                cursor = (0, cursor.1 + row_height + PADDING);
+                row_height = 0;
+            }
+
+            // start a new page when this one is full
+            if current.is_none() || cursor.1 + h > page_size {
+                current = Some(pages.len());
+                pages.push(RgbaImage::new(page_size, page_size));
+                cursor = (0, 0);
+                row_height = 0;
+            }
+
+            let page = current.unwrap();
+            imageops::replace(&mut pages[page], &image, cursor.0 as i64, cursor.1 as i64);
+            regions.insert(rl.to_string(), AtlasRegion { page, rect : Rect::new(cursor.0 as i32, cursor.1 as i32, w, h) });
+
+            cursor.0 += w + PADDING;
+            row_height = row_height.max(h);
+        }
+
+        Self {
+            pages,
+            regions
+        }
+    }
+}
+
+/// Every texture in the game, packed into as few GPU textures as possible. Textures are still looked up by the
+/// [`ResourceLocation`] of their original file, and [`AssetData`] UVs are remapped onto the atlas when they are drawn by
+/// [`draw_pp_texture`].
+///
+/// [`AssetData`]: crate::render::AssetData
+/// [`draw_pp_texture`]: crate::render::draw_pp_texture
+pub struct TextureAtlas<'a> {
+    pages : Vec<Texture<'a>>,
+    regions : HashMap<String, AtlasRegion>
+}
+
+impl<'a> TextureAtlas<'a> {
+
+    /// Upload the pages of a layout to the GPU
+    pub fn create(layout : AtlasLayout, texture_creator : &'a TextureCreator<WindowContext>) -> Result<Self, String> {
+        let mut pages = vec![];
+        for image in layout.pages.iter() {
+            let (w, h) = image.dimensions();
+            let mut texture = texture_creator.create_texture_static(PixelFormatEnum::RGBA32, w, h).map_err(|e| e.to_string())?;
+            texture.update(None, image.as_raw(), (w * 4) as usize).map_err(|e| e.to_string())?;
+            texture.set_blend_mode(BlendMode::Blend);
+            pages.push(texture);
+        }
+
+        Ok(Self {
+            pages,
+            regions : layout.regions
+        })
+    }
+
+    /// Get the page a texture is on and where it is, given the resource location of the original file as a string
+    pub fn get(&self, rl : &str) -> Option<(&Texture<'a>, AtlasRegion)> {
+        let region = self.regions.get(rl)?;
+        Some((&self.pages[region.page], *region))
+    }
+
+    /// An atlas page, given its index
+    pub fn get_page(&self, page : usize) -> &Texture<'a> {
+        &self.pages[page]
+    }
+
+    /// The number of textures uploaded to the GPU
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+}
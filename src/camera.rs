@@ -0,0 +1,158 @@
+use crate::collision::Aabb;
+use crate::render;
+
+/// The view into the world. The camera follows a point, usually the player, and decides where everything is drawn on
+/// screen. Every renderer converts world space coordinates to screen coordinates through the camera, see
+/// [`Camera::world_to_screen`].
+pub struct Camera {
+    /// The world space coordinates the camera is looking at, before it is clamped or shaken
+    pub position : (f32, f32),
+    /// How quickly the camera catches up with what it is following. Higher is faster, and 0 snaps straight to it
+    pub follow_speed : f32,
+    // where the camera was before the last tick, and how far between there and the position it is drawn
+    previous_position : (f32, f32),
+    alpha : f32,
+    // the area of the world the camera has to stay inside, if any
+    bounds : Option<Aabb>,
+    shake_intensity : f32,
+    shake_duration : f32,
+    // how long is left of the current shake
+    shake_time : f32,
+    // how long the camera has existed, used to move the shake around
+    timer : f32
+}
+
+impl Camera {
+
+    /// Create a camera looking at the origin, with no bounds
+    pub fn new() -> Self {
+        Self {
+            position : (0.0, 0.0),
+            follow_speed : 10.0,
+            previous_position : (0.0, 0.0),
+            alpha : 1.0,
+            bounds : None,
+            shake_intensity : 0.0,
+            shake_duration : 0.0,
+            shake_time : 0.0,
+            timer : 0.0
+        }
+    }
+
+    /// Move the camera towards a point. The further away it is, the faster it moves, so it eases in to the point
+    pub fn follow(&mut self, target : (f32, f32), delta : f32) {
+        if self.follow_speed <= 0.0 {
+            self.position = target;
+            return
+        }
+        // the same fraction of the distance is covered each second, no matter the frame rate
+        let t = 1.0 - (-self.follow_speed * delta).exp();
+        self.position = (
+            self.position.0 + (target.0 - self.position.0) * t,
+            self.position.1 + (target.1 - self.position.1) * t
+        );
+    }
+
+    /// Move the camera straight to a point, without easing between the old and new point when it is drawn
+    pub fn snap_to(&mut self, target : (f32, f32)) {
+        self.position = target;
+        self.previous_position = target;
+    }
+
+    /// Remember where the camera is before a tick moves it, so it can be drawn part of the way between the two. Until
+    /// [`Camera::interpolate`] is called again, the camera is at its latest position
+    pub fn begin_tick(&mut self) {
+        self.previous_position = self.position;
+        self.alpha = 1.0;
+    }
+
+    /// Look part of the way between where the camera was before the last tick and where it is now, from 0 to 1
+    pub fn interpolate(&mut self, alpha : f32) {
+        self.alpha = alpha.clamp(0.0, 1.0);
+    }
+
+    /// Keep the camera inside an area of the world, such as the level's extent. If the area is smaller than the screen the
+    /// camera stays in the middle of it. None lets the camera go anywhere
+    pub fn set_bounds(&mut self, bounds : Option<Aabb>) {
+        self.bounds = bounds;
+    }
+
+    /// Shake the camera by up to `intensity` pixels, calming down over `duration` seconds. A weaker shake doesn't replace a
+    /// stronger one that is still happening
+    pub fn shake(&mut self, intensity : f32, duration : f32) {
+        if intensity >= self.get_shake_strength() && duration > 0.0 {
+            self.shake_intensity = intensity;
+            self.shake_duration = duration;
+            self.shake_time = duration;
+        }
+    }
+
+    /// How far the camera is currently being shaken by, in pixels
+    pub fn get_shake_strength(&self) -> f32 {
+        if self.shake_time <= 0.0 {
+            return 0.0
+        }
+        self.shake_intensity * self.shake_time / self.shake_duration
+    }
+
+    /// Advance the screen shake
+    pub fn update(&mut self, delta : f32) {
+        self.timer += delta;
+        self.shake_time = (self.shake_time - delta).max(0.0);
+    }
+
+    /// The world space coordinates at the centre of the screen, after being clamped to the bounds and shaken
+    pub fn centre(&self) -> (f32, f32) {
+        let mut centre = (
+            self.previous_position.0 + (self.position.0 - self.previous_position.0) * self.alpha,
+            self.previous_position.1 + (self.position.1 - self.previous_position.1) * self.alpha
+        );
+
+        if let Some(bounds) = self.bounds {
+            let dims = render::get_actual_dimensions().lock().unwrap().get();
+            centre = (
+                clamp_axis(centre.0, bounds.x, bounds.w, dims.0 as f32),
+                clamp_axis(centre.1, bounds.y, bounds.h, dims.1 as f32)
+            );
+        }
+
+        // the shake doesn't need to be random, just fast and jittery. This way it is the same every run
+        let strength = self.get_shake_strength();
+        (centre.0 + strength * (self.timer * 47.0).sin(), centre.1 + strength * (self.timer * 61.0).cos())
+    }
+
+    /// Converts world space coordinates to screen coordinates
+    pub fn world_to_screen(&self, coords : (f32, f32)) -> (i32, i32) {
+        let (offset_x, offset_y) = self.offset();
+        ((coords.0 + offset_x as f32).floor() as i32, (coords.1 + offset_y as f32).floor() as i32)
+    }
+
+    /// Converts screen coordinates, such as the mouse position, to world space coordinates
+    pub fn screen_to_world(&self, coords : (i32, i32)) -> (f32, f32) {
+        let (offset_x, offset_y) = self.offset();
+        ((coords.0 - offset_x) as f32, (coords.1 - offset_y) as f32)
+    }
+
+    /// The area of the world that can be seen
+    pub fn visible_area(&self) -> Aabb {
+        render::visible_area(self.centre())
+    }
+
+    // how far the world is moved to be drawn on screen. The centre is rounded down so everything moves by whole pixels
+    // together
+    fn offset(&self) -> (i32, i32) {
+        let dims = render::get_actual_dimensions().lock().unwrap().get();
+        let centre = self.centre();
+        ((dims.0 / 2) as i32 - centre.0.floor() as i32, (dims.1 / 2) as i32 - centre.1.floor() as i32)
+    }
+}
+
+/// Keep the screen inside the bounds along one axis, or in the middle of them if they are smaller than the screen
+fn clamp_axis(centre : f32, start : f32, length : f32, screen : f32) -> f32 {
+    if length <= screen {
+        start + length / 2.0
+    }
+    else {
+        centre.clamp(start + screen / 2.0, start + length - screen / 2.0)
+    }
+}
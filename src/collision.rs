@@ -0,0 +1,84 @@
+use crate::level::Level;
+
+/// An axis aligned bounding box in world space, used for entity hitboxes and tile collision boxes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub x : f32,
+    pub y : f32,
+    pub w : f32,
+    pub h : f32
+}
+
+impl Aabb {
+
+    /// Create a box given its top left corner and its size
+    pub fn new(x : f32, y : f32, w : f32, h : f32) -> Self {
+        Self { x, y, w, h }
+    }
+
+    /// Create a box of a given size, centred on some coordinates
+    pub fn centred(coords : (f32, f32), size : (f32, f32)) -> Self {
+        Self::new(coords.0 - size.0 / 2.0, coords.1 - size.1 / 2.0, size.0, size.1)
+    }
+
+    pub fn right(&self) -> f32 {
+        self.x + self.w
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.y + self.h
+    }
+
+    /// Whether two boxes overlap. Boxes that only touch along an edge don't count
+    pub fn intersects(&self, other : &Aabb) -> bool {
+        self.x < other.right() && other.x < self.right() && self.y < other.bottom() && other.y < self.bottom()
+    }
+
+    /// Returns the box moved by a given amount
+    pub fn translate(&self, amount : (f32, f32)) -> Self {
+        Self::new(self.x + amount.0, self.y + amount.1, self.w, self.h)
+    }
+
+    /// The smallest box containing both boxes
+    pub fn union(&self, other : &Aabb) -> Self {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Self::new(x, y, self.right().max(other.right()) - x, self.bottom().max(other.bottom()) - y)
+    }
+}
+
+/// Moves a hitbox through the level by a given amount, stopping at anything solid and sliding along it. Returns how far the
+/// hitbox actually moved. The x and y axis are resolved separately, which is what lets it slide along walls.
+///
+/// Anything the hitbox is already overlapping is ignored, so an entity spawned inside a wall can still walk out of it.
+pub fn move_and_slide(level : &Level, hitbox : Aabb, amount : (f32, f32)) -> (f32, f32) {
+    let start = hitbox;
+
+    // x axis
+    let mut dx = amount.0;
+    if dx != 0.0 {
+        let target = hitbox.translate((dx, 0.0));
+        for collider in level.get_colliders(&hitbox.union(&target)) {
+            if collider.intersects(&start) || !collider.intersects(&hitbox.translate((dx, 0.0))) {
+                continue
+            }
+            // move up to the edge of the collider
+            dx = if dx > 0.0 { collider.x - hitbox.right() } else { collider.right() - hitbox.x };
+        }
+    }
+    let hitbox = hitbox.translate((dx, 0.0));
+
+    // y axis, from where the x axis ended up
+    let mut dy = amount.1;
+    if dy != 0.0 {
+        let target = hitbox.translate((0.0, dy));
+        for collider in level.get_colliders(&hitbox.union(&target)) {
+            if collider.intersects(&start) || !collider.intersects(&hitbox.translate((0.0, dy))) {
+                continue
+            }
+            dy = if dy > 0.0 { collider.y - hitbox.bottom() } else { collider.bottom() - hitbox.y };
+        }
+    }
+
+    (dx, dy)
+}
@@ -0,0 +1,72 @@
+use uuid::Uuid;
+use crate::game::{DyslexiaMode, Game};
+use crate::input::{Action, Binding};
+use crate::level::Level;
+use crate::resource_location::ResourceLocation;
+use crate::screen::Screen;
+use crate::tile::{Tile, TileSize};
+
+/// A change to the game instance, requested by an entity, screen or widget whilst the game is part way through iterating over its
+/// entities or screen. Commands are queued up in the [`CommandQueue`] and applied by the game once it is safe to do so.
+pub enum Command {
+    /// Spawn an entity given its resource location and world space coordinates, see [`Game::spawn`]
+    Spawn(ResourceLocation, (f32, f32)),
+    /// Remove an entity from the game
+    Despawn(Uuid),
+    /// Change an entity's health by a given amount, positive or negative
+    ChangeHealth(Uuid, f32),
+    /// Play a sound given its resource location, see [`Game::play_sound`]
+    PlaySound(ResourceLocation),
+    /// Replace the current screen with the one returned by the given function
+    ChangeScreen(fn(&mut Game) -> Box<dyn Screen>),
+    /// Set or remove a tile in the current level, and rebuild its nav
+    SetTile(TileSize, (i32, i32), Option<Tile>),
+    /// Replace the current level, without spawning its entities
+    SetLevel(Level),
+    /// Save the current level to a file, see [`Level::save`]. Applied in order, so any tiles set before it are saved too
+    SaveLevel(String),
+    SetDyslexiaMode(DyslexiaMode),
+    /// Change one of the keys that does an action, see [`Keybindings::bind`]. The keybindings are saved if the game has
+    /// somewhere to save them
+    ///
+    /// [`Keybindings::bind`]: crate::input::Keybindings::bind
+    Rebind(Action, usize, Binding),
+    /// Close the game
+    Quit,
+    /// The player has died, unload the level and display the death screen. This is always applied after every other
+    /// command in the queue
+    GameOver
+}
+
+/// A list of [`Command`]s waiting to be applied, in the order they were pushed
+pub struct CommandQueue {
+    commands : Vec<Command>
+}
+
+impl CommandQueue {
+
+    /// Create an empty queue
+    pub fn new() -> Self {
+        Self {
+            commands : vec![]
+        }
+    }
+
+    /// Add a command to the back of the queue
+    pub fn push(&mut self, command : Command) {
+        self.commands.push(command)
+    }
+
+    /// Remove every command from the queue, returning them in the order they were pushed
+    pub fn take(&mut self) -> Vec<Command> {
+        std::mem::take(&mut self.commands)
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
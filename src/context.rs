@@ -0,0 +1,74 @@
+use std::sync::{Arc, Mutex};
+use sdl2::event::Event;
+use sdl2::keyboard::Scancode;
+use uuid::Uuid;
+use crate::camera::Camera;
+use crate::collision::Aabb;
+use crate::command::{Command, CommandQueue};
+use crate::entity::Entity;
+use crate::entity_list::EntityList;
+use crate::game::DyslexiaMode;
+use crate::gamepad::Gamepad;
+use crate::input::Keybindings;
+use crate::level::Level;
+use crate::resource_location::ResourceLocation;
+use crate::spatial::SpatialGrid;
+
+/// What an entity, screen or widget can see of the game instance whilst it is being run, given to it by the [`Game`] in
+/// [`Entity::tick`], [`Screen::cycle`] and [`Widget::on_click`]. The game can only be read from here, any changes to it are
+/// made by pushing a [`Command`], which the game applies once it is done iterating through its entities and screen.
+///
+/// [`Game`]: crate::game::Game
+/// [`Entity::tick`]: Entity::tick
+/// [`Screen::cycle`]: crate::screen::Screen::cycle
+/// [`Widget::on_click`]: crate::widget::Widget::on_click
+pub struct Context<'a> {
+    pub level : Option<&'a Level>,
+    pub entities : &'a EntityList,
+    pub player : Option<Uuid>,
+    pub held_keys : &'a Vec<Scancode>,
+    pub events : &'a Vec<Event>,
+    pub score : f32,
+    pub dyslexia_mode : DyslexiaMode,
+    pub camera : &'a Camera,
+    /// Which keys do each action, see [`Keybindings::is_held`] and [`Keybindings::is_pressed`]
+    pub keybindings : &'a Keybindings,
+    /// The state of the game controllers, see [`Gamepad::movement`]
+    pub gamepad : &'a Gamepad,
+    // where every entity was at the start of the physics loop, searched through Context::overlaps and the like
+    pub(crate) spatial : &'a SpatialGrid,
+    // only the game fills this in, everything else pushes to it through Context::push
+    pub(crate) commands : &'a mut CommandQueue
+}
+
+impl<'a> Context<'a> {
+
+    /// Returns the entity assigned as the "player", see [`Game::get_player`]
+    ///
+    /// [`Game::get_player`]: crate::game::Game::get_player
+    pub fn get_player(&self) -> Option<Arc<Mutex<dyn Entity>>> {
+        self.entities.get(&self.player?)
+    }
+
+    /// Returns the Uuid of every entity whose hitbox overlaps an area, see [`Game::overlaps`]
+    ///
+    /// [`Game::overlaps`]: crate::game::Game::overlaps
+    pub fn overlaps(&self, area : &Aabb) -> Vec<Uuid> {
+        self.spatial.query(area).iter().map(|e| e.uuid).collect()
+    }
+
+    /// Returns the Uuid of every entity within a radius of a point, see [`Context::overlaps`]
+    pub fn within_radius(&self, coords : (f32, f32), radius : f32) -> Vec<Uuid> {
+        self.spatial.within_radius(coords, radius).iter().map(|e| e.uuid).collect()
+    }
+
+    /// Returns the Uuid of the closest entity with the given resource location, see [`Context::overlaps`]
+    pub fn nearest_of_type(&self, coords : (f32, f32), rl : &ResourceLocation) -> Option<Uuid> {
+        self.spatial.nearest_of_type(coords, rl).map(|e| e.uuid)
+    }
+
+    /// Queue up a change to the game, applied once it is safe to do so
+    pub fn push(&mut self, command : Command) {
+        self.commands.push(command)
+    }
+}
@@ -1,9 +1,9 @@
-pub(crate) mod player;
-pub(crate) mod enemy;
-pub(crate) mod dummy;
-pub(crate) mod turret;
-pub(crate) mod floaty_bomb;
-mod explosion;
+pub mod player;
+pub mod enemy;
+pub mod dummy;
+pub mod turret;
+pub mod floaty_bomb;
+pub mod explosion;
 
 use crate::entity::{Entity};
 
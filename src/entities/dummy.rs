@@ -1,6 +1,5 @@
 use uuid::Uuid;
 use crate::entity::Entity;
-use crate::game::Game;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
 use crate::utils::create_uuid;
@@ -14,10 +13,8 @@ pub struct DummyEntity {
     // hitbox : matrix,
     velocity : (f32, f32),
     uuid : Uuid,
-    game : *mut Game,
     health : f32,
     resource_location: ResourceLocation,
-    index : usize,
 }
 
 impl Entity for DummyEntity {
@@ -49,8 +46,8 @@ impl Entity for DummyEntity {
         &self.resource_location
     }
 
-    fn get_index(&self) -> usize {
-        self.index
+    fn get_uuid(&self) -> Uuid {
+        self.uuid
     }
 
     fn get_velocity(&mut self) -> (f32, f32) {
@@ -71,7 +68,7 @@ impl Entity for DummyEntity {
 }
 
 impl DummyEntity {
-    pub fn create(game : &mut Game, ass : AssetData) -> DummyEntity {
+    pub fn create(ass : AssetData) -> DummyEntity {
 
         let uuid = create_uuid();
 
@@ -82,10 +79,8 @@ impl DummyEntity {
             asset_data: ass,
             velocity: (0.0, 0.0),
             uuid,
-            game,
             health: 0.0,
             resource_location: rl,
-            index : game.entities.len()
         }
     }
 }
\ No newline at end of file
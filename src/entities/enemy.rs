@@ -1,24 +1,27 @@
 use sdl2::rect::Rect;
 use std::sync::Mutex;
 use uuid::Uuid;
+use crate::animation::Animator;
+use crate::context::Context;
 use crate::entity::{Entity};
+use crate::entity_definition::EntityDefinition;
 use crate::game::Game;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
 use crate::utils::create_uuid;
 
 // This was used during development and doesn't do anything
-// it is very similar to the other entity objects
+// it is very similar to the other entity objects. It is created for any entity with the "idle" behaviour
 
 pub struct Enemy {
     coords: (f32, f32),
     pub asset_data: AssetData,
     velocity : (f32, f32),
     uuid : Uuid,
-    game : *mut Game,
     resource_location: ResourceLocation,
     health : f32,
-    index : usize,
+    hitbox : (f32, f32),
+    animator : Animator,
 }
 
 impl Entity for Enemy {
@@ -31,11 +34,15 @@ impl Entity for Enemy {
     }
 
     fn get_asset_data(&mut self) -> AssetData {
-        AssetData {
+        self.animator.get_asset_data().unwrap_or(AssetData {
             uv: self.asset_data.uv.clone(),
             origin: self.asset_data.origin.clone(),
             resource_location: self.asset_data.resource_location.clone()
-        }
+        })
+    }
+
+    fn tick(&mut self, delta: f32, ctx: &mut Context) {
+        self.animator.tick(delta);
     }
 
     fn get_velocity(&mut self) -> (f32, f32) {
@@ -54,36 +61,34 @@ impl Entity for Enemy {
         &self.resource_location
     }
 
-    fn get_index(&self) -> usize {
-        self.index
+    fn get_uuid(&self) -> Uuid {
+        self.uuid
     }
 
     fn change_health(&mut self, amount: f32) {
-        todo!()
+        self.health += amount
+    }
+
+    fn get_hitbox_size(&self) -> (f32, f32) {
+        self.hitbox
     }
 }
 
 impl Enemy {
-    pub fn create(game: &mut Game) {
-        let asset_data = AssetData {
-            uv: Option::from(Rect::new(0, 0, 32, 32)),
-            origin: (16, 22),
-            resource_location: ResourceLocation::new("game", "entity/enemy.png"),
-        };
-
+    pub fn create(game: &mut Game, definition : &EntityDefinition) -> Uuid {
         let uuid = create_uuid();
 
         let mut entity = Self{
             coords: (0.0,0.0),
-            asset_data,
+            asset_data : definition.asset_data.clone(),
             velocity: (0.0, 0.0),
             uuid, // will be from hash function
-            game,
-            resource_location: ResourceLocation::new("game", "entity/enemy"),
-            health: 15.0,
-            index: game.entities.len(),
+            resource_location: definition.resource_location.clone(),
+            health: definition.health,
+            hitbox : definition.hitbox,
+            animator : Animator::from_definition(game, definition),
         };
         let ret = Box::new(Mutex::new(entity));
-        game.entities.push(ret);
+        game.entities.insert(ret)
     }
 }
\ No newline at end of file
@@ -1,8 +1,10 @@
 use std::sync::Mutex;
-use image::math::Rect;
-use num::clamp;
 use uuid::Uuid;
+use crate::animation::Animator;
+use crate::command::Command;
+use crate::context::Context;
 use crate::entity::Entity;
+use crate::entity_definition::EntityDefinition;
 use crate::game::Game;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
@@ -11,11 +13,10 @@ use crate::utils::create_uuid;
 /// When created it will play an explosion animation
 pub struct Explosion {
     coords : (f32, f32),
-    timer : f32,
-    frames: Vec<AssetData>,
+    animator : Animator,
+    asset_data : AssetData,
+    hitbox : (f32, f32),
     resource_location: ResourceLocation,
-    index : usize,
-    game : *mut Game,
     uuid : Uuid
 
 }
@@ -41,21 +42,24 @@ impl Entity for Explosion {
         // no
     }
 
-    fn tick(&mut self, delta: f32) {
-        // increase timer, or remove self when timer complete
-        if self.timer > 0.75 {
-            let game = unsafe { &mut *self.game };
-            game.entities.remove(self.index - 1);
+    fn tick(&mut self, delta: f32, ctx: &mut Context) {
+        // remove self once the animation is over, or straight away if there isn't one
+        let event = self.animator.tick(delta);
+        if event.as_deref() == Some("despawn") || self.animator.current().is_none() {
+            ctx.push(Command::Despawn(self.uuid));
         }
-        self.timer += delta;
     }
 
     fn get_resource_location(&self) -> &ResourceLocation {
         &self.resource_location
     }
 
-    fn get_index(&self) -> usize {
-        self.index
+    fn get_uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn get_hitbox_size(&self) -> (f32, f32) {
+        self.hitbox
     }
 
     fn get_velocity(&mut self) -> (f32, f32) {
@@ -67,72 +71,35 @@ impl Entity for Explosion {
     }
 
     fn get_asset_data(&mut self) -> AssetData {
-        // play back the animation at 8 frames per second
-        let frame = clamp((self.timer / 0.125) as usize, 0, 5);
-        self.frames[frame].clone()
+        self.animator.get_asset_data().unwrap_or(self.asset_data.clone())
     }
 }
 
 impl Explosion {
-    pub fn create(game : &mut Game, coords : (f32, f32)) {
-        // register frames
-        let frame1 = AssetData {
-            uv: Option::from(sdl2::rect::Rect::new(0, 0, 64, 64)),
-            origin: (32, 32),
-            resource_location: ResourceLocation::new("game", "entity/explosion/explosion.png"),
-        };
-        let frame2 = AssetData {
-            uv: Option::from(sdl2::rect::Rect::new(0, 64, 64, 64)),
-            origin: (32, 32),
-            resource_location: ResourceLocation::new("game", "entity/explosion/explosion.png"),
-        };
-        let frame3 = AssetData {
-            uv: Option::from(sdl2::rect::Rect::new(0, 128, 64, 64)),
-            origin: (32, 32),
-            resource_location: ResourceLocation::new("game", "entity/explosion/explosion.png"),
-        };
-        let frame4 = AssetData {
-            uv: Option::from(sdl2::rect::Rect::new(0, 192, 64, 64)),
-            origin: (32, 32),
-            resource_location: ResourceLocation::new("game", "entity/explosion/explosion.png"),
-        };
-        let frame5 = AssetData {
-            uv: Option::from(sdl2::rect::Rect::new(0, 256, 64, 64)),
-            origin: (32, 32),
-            resource_location: ResourceLocation::new("game", "entity/explosion/explosion.png"),
-        };
-        let frame6 = AssetData {
-            uv: Option::from(sdl2::rect::Rect::new(0, 320, 64, 64)),
-            origin: (32, 32),
-            resource_location: ResourceLocation::new("game", "entity/explosion/explosion.png"),
-        };
-
-        // push frames to a list
-        let mut frames = vec![];
-        frames.push(frame1);
-        frames.push(frame2);
-        frames.push(frame3);
-        frames.push(frame4);
-        frames.push(frame5);
-        frames.push(frame6);
-
+    /// Create an explosion from its definition. It plays its `idle` animation, and is removed when the animation sends the
+    /// `despawn` event. Parameters:
+    /// - `sound` : played when the explosion is created
+    /// - `shake` : how far the camera is shaken in pixels, defaults to 3
+    /// - `shake_time` : how long the camera shakes for in seconds, defaults to 0.3
+    pub fn create(game : &mut Game, definition : &EntityDefinition) -> Uuid {
         let uuid = create_uuid();
 
         // create entity instance
         let mut explosion = Self {
-            coords,
-            timer: 0.0,
+            coords : (0.0, 0.0),
             uuid,
-            game,
-            resource_location : ResourceLocation::new("game", "entity/explosion"),
-            index : game.entities.len(),
-            frames,
+            resource_location : definition.resource_location.clone(),
+            animator : Animator::from_definition(game, definition),
+            asset_data : definition.asset_data.clone(),
+            hitbox : definition.hitbox
         };
 
 
         let ret = Box::new(Mutex::new(explosion));
         // push entity to the entity list
-        game.entities.push(ret);
-        game.play_sound(ResourceLocation::new("game", "sounds/entity/explosion/explosion.ogg"))
+        let uuid = game.entities.insert(ret);
+        game.play_sound(definition.get_resource_location_parameter("sound", ResourceLocation::new("game", "sounds/entity/explosion/explosion.ogg")));
+        game.camera.shake(definition.get_parameter("shake", 3.0), definition.get_parameter("shake_time", 0.3));
+        uuid
     }
 }
\ No newline at end of file
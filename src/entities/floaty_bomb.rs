@@ -3,23 +3,33 @@ use num::abs;
 use sdl2::keyboard::Keycode::N;
 use sdl2::rect::Rect;
 use uuid::Uuid;
-use crate::entities::explosion::Explosion;
+use crate::animation::Animator;
+use crate::collision::move_and_slide;
+use crate::command::Command;
+use crate::context::Context;
 use crate::entity::Entity;
+use crate::entity_definition::EntityDefinition;
 use crate::game::Game;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
-use crate::utils::{create_uuid, get_dist, mul_vec, normalise_vec};
+use crate::utils::{create_uuid, mul_vec, normalise_vec};
 
 pub struct FloatyBomb {
     coords : (f32, f32),
     health : f32,
     velocity : (f32,f32),
+    speed : f32,
+    hitbox : (f32, f32),
+    damage : f32,
+    // the entity spawned when it explodes
+    explosion : ResourceLocation,
     asset_data: AssetData,
+    animator : Animator,
     resource_location: ResourceLocation,
-    index : usize,
     uuid : Uuid,
-    game : *mut Game,
-    target : Option<(f32, f32)>
+    target : Option<(f32, f32)>,
+    // set when the bomb's hitbox overlaps the player's
+    touching_player : bool
 }
 
 impl Entity for FloatyBomb {
@@ -39,58 +49,73 @@ impl Entity for FloatyBomb {
         self.health += amount
     }
 
-    fn tick(&mut self, delta: f32) {
-        // get the game instance
-        let game = unsafe { &mut *self.game };
-        // if there is no current target OR current target = current coords
-        if self.target.is_none() || self.target == Some((
-            (((self.coords.0 as i32 / 16) * 16) + 8) as f32,
-            (((self.coords.1 as i32 / 16) * 16) + 8) as f32)
-        ) {
-            // get player coords
-            let player = game.get_player().unwrap().lock().unwrap().get_coords();
-            // get player in the tile coords (nav tiles x16)
-            let player_tile = (
-                ((player.0 as i32 / 16) * 16) + 8,
-                ((player.1 as i32 / 16) * 16) + 8 );
-            // generate the path
-            let path = game.current_level.as_mut().unwrap()
-                .tile_nav.path_to(
-                    self.coords.0 as i32,
-                    self.coords.1 as i32, player_tile.0, player_tile.1);
-            // if the second position exists, set that to current target
-            if path.get(1).is_some() {
-                self.target = Some((
-                    (path.get(1).unwrap().clone().0 + 8) as f32,
-                    (path.get(1).unwrap().clone().1 + 8) as f32));
+    fn tick(&mut self, delta: f32, ctx: &mut Context) {
+        self.animator.tick(delta);
+
+        // if there is no current target OR current target = current coords OR it has hit the player
+        if self.touching_player || self.target.is_none() || self.target == Some(tile_centre(self.coords)) {
+            // follow the level's flow field towards the player
+            let next = ctx.level.and_then(|level| level.next_step(self.coords));
+            // if the next position exists, set that to current target
+            if next.is_some() {
+                self.target = next;
             }
-            // if the second position does not exist, and distance is less
-            // than 20 pixels
-            if path.get(1).is_none() || get_dist(&self.coords, &player) < 20 {
+            // if the bomb is on the same tile as the player, or has touched the player
+            let same_tile = next.is_some() && next == Some(tile_centre(self.coords));
+            if same_tile || self.touching_player {
                 // spawn an explosion
-                Explosion::create(game, self.coords);
-                // remove 10 health points from the player
-                game.get_player().unwrap().lock().unwrap().change_health(-10.0);
+                ctx.push(Command::Spawn(self.explosion.clone(), self.coords));
+                // remove health points from the player
+                if let Some(player) = ctx.player {
+                    ctx.push(Command::ChangeHealth(player, -self.damage));
+                }
                 // remove the floaty bomb from the entity list
-                game.entities.remove(self.index);
+                ctx.push(Command::Despawn(self.uuid));
             }
 
         }
+        // if the player can't be reached, wait where it is
+        if self.target.is_none() {
+            self.set_velocity((0.0, 0.0));
+            return
+        }
         // create a normalised vector in the direction from the current
         // position to the target position
         let mut normalised = normalise_vec((self.target.unwrap().0 - self.coords.0, self.target.unwrap().1 - self.coords.1));
-        // multiply by 20
-        mul_vec(&mut normalised, 20.0);
+        // multiply by the speed
+        mul_vec(&mut normalised, self.speed);
         // set the current velocity
         self.set_velocity(normalised);
     }
 
+    fn get_hitbox_size(&self) -> (f32, f32) {
+        self.hitbox
+    }
+
+    fn on_collide(&mut self, other: Uuid, ctx: &mut Context) {
+        if ctx.player == Some(other) {
+            self.touching_player = true;
+        }
+    }
+
+    fn physics(&mut self, delta: f32, ctx: &mut Context) {
+        self.tick(delta, ctx);
+
+        let movement = (self.velocity.0 * delta, self.velocity.1 * delta);
+
+        // stop at walls instead of floating through them
+        if let Some(level) = ctx.level {
+            let (dx, dy) = move_and_slide(level, self.get_hitbox(), movement);
+            self.coords = (self.coords.0 + dx, self.coords.1 + dy);
+        }
+    }
+
     fn get_resource_location(&self) -> &ResourceLocation {
         &self.resource_location
     }
 
-    fn get_index(&self) -> usize {
-        self.index
+    fn get_uuid(&self) -> Uuid {
+        self.uuid
     }
 
     fn get_velocity(&mut self) -> (f32, f32) {
@@ -102,39 +127,46 @@ impl Entity for FloatyBomb {
     }
 
     fn get_asset_data(&mut self) -> AssetData {
-        self.asset_data.clone()
+        self.animator.get_asset_data().unwrap_or(self.asset_data.clone())
     }
 }
 
 impl FloatyBomb {
 
-    pub fn create(game: &mut Game, coords : (f32, f32)) {
-
-        let asset_data = AssetData {
-            uv: Option::from(Rect::new(0, 0, 16, 16)),
-            origin: (8, 8),
-            resource_location: ResourceLocation::new("game", "entity/floaty_bomb/floaty_bomb.png"),
-        };
+    /// Create a floaty bomb from its definition. Parameters:
+    /// - `damage` : how much health the player loses when it explodes, defaults to 10
+    /// - `explosion` : the entity spawned when it explodes
+    pub fn create(game: &mut Game, definition : &EntityDefinition) -> Uuid {
 
         let uuid = create_uuid();
 
         let mut floaty_bomb = Self {
-            coords,
-            asset_data,
+            coords : (0.0, 0.0),
+            asset_data : definition.asset_data.clone(),
+            animator : Animator::from_definition(game, definition),
             velocity: (0.0, 0.0),
             uuid,
-            game,
-            health : 12.0,
-            resource_location : ResourceLocation::new("game", "entity/floaty_bomb"),
-            index : game.entities.len(),
+            health : definition.health,
+            speed : definition.speed,
+            hitbox : definition.hitbox,
+            damage : definition.get_parameter("damage", 10.0),
+            explosion : definition.get_resource_location_parameter("explosion", ResourceLocation::new("game", "entity/explosion")),
+            resource_location : definition.resource_location.clone(),
             target: None,
+            touching_player: false,
         };
 
         let ret = Box::new(Mutex::new(floaty_bomb));
 
-        game.entities.push(ret);
+        game.entities.insert(ret)
 
     }
 
 
-}
\ No newline at end of file
+}
+
+/// The centre of the nav tile a point is on. Floored rather than truncated, so points left of or above the origin are on
+/// the right tile
+fn tile_centre(coords : (f32, f32)) -> (f32, f32) {
+    ((coords.0 / 16.0).floor() * 16.0 + 8.0, (coords.1 / 16.0).floor() * 16.0 + 8.0)
+}
@@ -6,12 +6,17 @@ use sdl2::event::{Event, EventPollIterator};
 use sdl2::EventPump;
 use sdl2::keyboard::{Keycode, Scancode};
 use uuid::Uuid;
+use crate::animation::Animator;
+use crate::collision::move_and_slide;
+use crate::command::Command;
+use crate::context::Context;
 use crate::entity::{Entity};
+use crate::entity_definition::EntityDefinition;
 use crate::game::Game;
+use crate::gamepad::Gamepad;
+use crate::input::{Action, Keybindings};
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
-use crate::screen::Screen;
-use crate::screens::you_died::DeathScreen;
 use crate::tile::{TileSize, TileType};
 use crate::utils::{create_uuid, mul_vec, normalise_vec};
 
@@ -23,43 +28,52 @@ pub struct Player {
     // hitbox : matrix,
     velocity : (f32, f32),
     uuid : Uuid,
-    game : *mut Game,
     health : f32,
+    speed : f32,
+    hitbox : (f32, f32),
+    // plays "walk" whilst moving and "idle" otherwise
+    animator : Animator,
     resource_location: ResourceLocation,
-    index : usize,
 }
 
 impl Entity for Player {
-    fn tick(&mut self, delta: f32) {
+    fn tick(&mut self, delta: f32, ctx: &mut Context) {
 
-        // on death display the death screen and unload the level
+        // on death tell the game to display the death screen and unload the level. This can't be done
+        // here, as the entity list can't be cleared whilst it is being iterated through
         if self.health <= 0.0 {
-            let game = unsafe { &mut *self.game };
-            game.entities.clear();
-            game.current_level = None;
-            game.current_screen = Some(DeathScreen::create(game));
+            ctx.push(Command::GameOver);
         }
 
+        self.animator.tick(delta);
 
     }
 
     // this function was made before the tick function was added, if I did this now I would
     // use a different approach
-    fn physics(&mut self, delta: f32) {
+    fn physics(&mut self, delta: f32, ctx: &mut Context) {
 
-        self.tick(delta);
+        self.tick(delta, ctx);
 
-        let game = self.game.clone();
+        self.handle_input(ctx.held_keys, ctx.keybindings, ctx.gamepad);
 
-        unsafe { self.handle_input((*game).held_keys.clone(), (*game).events.clone()) }
+        if self.velocity == (0.0, 0.0) {
+            self.animator.play("idle");
+        }
+        else {
+            self.animator.play("walk");
+        }
 
-        let prev_coords = self.coords;
+        let movement = (self.get_velocity().0 * delta, self.get_velocity().1 * delta);
 
-        let mut x = self.get_coords().0 + self.get_velocity().0 * delta;
-        let mut y = self.get_coords().1 + self.get_velocity().1 * delta;
+        // stop at walls and slide along them
+        let (dx, dy) = match ctx.level {
+            Some(level) => { move_and_slide(level, self.get_hitbox(), movement) }
+            None => { (0.0, 0.0) }
+        };
 
-        x = if self.get_in_wall((x, prev_coords.1)) {prev_coords.0} else {x};
-        y = if self.get_in_wall((prev_coords.0, y)) {prev_coords.1} else {y};
+        let x = self.coords.0 + dx;
+        let y = self.coords.1 + dy;
 
         self.set_coords((x,y))
     }
@@ -77,7 +91,7 @@ impl Entity for Player {
     }
 
     fn get_asset_data(&mut self) -> AssetData {
-        self.asset_data.clone()
+        self.animator.get_asset_data().unwrap_or(self.asset_data.clone())
     }
 
     fn get_velocity(&mut self) -> (f32, f32) {
@@ -92,91 +106,76 @@ impl Entity for Player {
         &self.resource_location
     }
 
-    fn get_index(&self) -> usize {
-        self.index
+    fn get_uuid(&self) -> Uuid {
+        self.uuid
     }
 
     fn change_health(&mut self, amount: f32) {
         self.health += amount;
     }
+
+    fn get_hitbox_size(&self) -> (f32, f32) {
+        self.hitbox
+    }
 }
 
 
 impl Player {
-    pub fn create(game: &mut Game) {
+    /// Create the player from its definition, and assign it as the game's player. There can only be one player, so nothing
+    /// is created if there already is one
+    pub fn create(game: &mut Game, definition : &EntityDefinition) -> Option<Uuid> {
         if game.player.is_none() {
-            let asset_data = AssetData {
-                uv: Option::from(Rect::new(0, 0, 32, 32)),
-                origin: (16, 22),
-                resource_location: ResourceLocation::new("game", "entity/player.png"),
-            };
-
             let uuid = create_uuid();
 
             let mut player = Self {
                 coords: (0.0, 0.0),
-                asset_data,
+                asset_data : definition.asset_data.clone(),
                 velocity: (0.0, 0.0),
                 uuid,
-                game,
-                health : 20.0,
-                resource_location : ResourceLocation::new("game", "entity/player"),
-                index : game.entities.len()
+                health : definition.health,
+                speed : definition.speed,
+                hitbox : definition.hitbox,
+                animator : Animator::from_definition(game, definition),
+                resource_location : definition.resource_location.clone(),
             };
 
             let ret = Box::new(Mutex::new(player));
 
-            game.player = Some(ret.lock().unwrap().index);
-            game.entities.push(ret);
-            //game.player = Some(ret.clone());
+            game.player = Some(game.entities.insert(ret));
+            game.player
         }
         else {
-            warn!("Player already exists in instance! @ index {}", game.player.unwrap())
+            warn!("Player already exists in instance! @ uuid {}", game.player.unwrap());
+            None
         }
     }
 
-    pub fn get_in_wall(&mut self, coords : (f32, f32)) -> bool {
-
-        let game_level = unsafe { &mut *self.game }.current_level.as_mut();
-
-        if game_level.is_none() {
-            return true
-        }
-
-        let px = (if coords.0 < 0.0 {coords.0 - 1.0} else { coords.0 }) as i32;
-        let py = (if coords.1 < 0.0 {coords.1 - 1.0} else { coords.1 }) as i32;
+    pub fn handle_input(&mut self, held_keys: &[Scancode], keybindings: &Keybindings, gamepad: &Gamepad) {
+        // replace with an actual drag constant in the physics loop
+        //self.set_velocity((0.0, 0.0));
 
-        if game_level.unwrap().tile_nav.get_tile(px, py).get_type() == TileType::WALL {
-            return true
+        // the stick takes over from the keys whilst it is pushed, moving slower the less it is pushed
+        if let Some(movement) = gamepad.movement() {
+            self.set_velocity((movement.0 * self.speed, movement.1 * self.speed));
+            return
         }
-        false
-    }
 
-    pub fn handle_input(&mut self, held_keys: Vec<Scancode>, events: Vec<Event>) {
-        // replace with an actual drag constant in the physics loop
-        //self.set_velocity((0.0, 0.0));
         let mut ret_vel = (0.0, 0.0);
 
-
-        for key in held_keys {
-            match key {
-                Scancode::W => {
-                    ret_vel.1 -= 1.0;
-                }
-                Scancode::S => {
-                    ret_vel.1 += 1.0;
-                }
-                Scancode::D => {
-                    ret_vel.0 += 1.0;
-                }
-                Scancode::A => {
-                    ret_vel.0 -= 1.0;
-                }
-                _ => {}
-            }
+        if keybindings.is_held(Action::MoveUp, held_keys) {
+            ret_vel.1 -= 1.0;
+        }
+        if keybindings.is_held(Action::MoveDown, held_keys) {
+            ret_vel.1 += 1.0;
+        }
+        if keybindings.is_held(Action::MoveRight, held_keys) {
+            ret_vel.0 += 1.0;
+        }
+        if keybindings.is_held(Action::MoveLeft, held_keys) {
+            ret_vel.0 -= 1.0;
         }
         let mut norm = normalise_vec(ret_vel);
-        mul_vec(&mut norm, 60.0);
+        mul_vec(&mut norm, self.speed);
         self.set_velocity(norm);
     }
 }
\ No newline at end of file
@@ -2,7 +2,11 @@ use std::sync::Mutex;
 use num::integer::sqrt;
 use sdl2::rect::Rect;
 use uuid::Uuid;
+use crate::animation::Animator;
+use crate::command::Command;
+use crate::context::Context;
 use crate::entity::Entity;
+use crate::entity_definition::EntityDefinition;
 use crate::game::Game;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
@@ -13,39 +17,59 @@ pub struct Turret {
     asset_data: AssetData,
     velocity : (f32, f32),
     uuid : Uuid,
-    game : *mut Game,
     health : f32,
     facing : Facing,
+    // the texture for each direction, in the same order as Facing
+    facing_textures : Vec<ResourceLocation>,
     resource_location: ResourceLocation,
-    index : usize,
+    hitbox : (f32, f32),
+    range : f32,
+    fire_rate : f32,
+    damage : f32,
+    sound : ResourceLocation,
+    // plays "fire" for each shot, showing the facing texture otherwise
+    animator : Animator,
     timer : f32
 }
 
 
 impl Turret {
-    pub fn create(game: &mut Game) {
-        let asset_data = AssetData {
-            uv: Option::from(Rect::new(0, 0, 32, 32)),
-            origin: (16, 22),
-            resource_location: ResourceLocation::new("game", "entity/turret/turret_se.png"),
-        };
-
+    /// Create a turret from its definition. Parameters:
+    /// - `range` : how close the player has to be to be shot at, defaults to 150
+    /// - `fire_rate` : the time between shots in seconds, defaults to 0.5
+    /// - `damage` : defaults to 1
+    /// - `sound` : played for each shot
+    /// - `facing` : the texture for each direction the turret can face, `e`, `se`, `s`, `sw` and `w`
+    ///
+    /// Its `fire` animation, if it has one, is played over the facing texture for each shot
+    pub fn create(game: &mut Game, definition : &EntityDefinition) -> Uuid {
         let uuid = create_uuid();
 
+        let facing_textures = ["e", "se", "s", "sw", "w"].iter()
+            .map(|key| definition.get_resource_location_parameter(
+                format!("facing.{}", key).as_str(),
+                definition.asset_data.resource_location.clone()))
+            .collect();
+
         let mut entity = Self{
             coords: (0.0,0.0),
-            asset_data,
+            asset_data : definition.asset_data.clone(),
             velocity: (0.0, 0.0),
             uuid, // will be from hash function
-            game,
-            resource_location: ResourceLocation::new("game", "entity/turret"),
-            health: 15.0,
+            resource_location: definition.resource_location.clone(),
+            health: definition.health,
             facing: Facing::SE,
-            index : game.entities.len(),
+            facing_textures,
+            hitbox : definition.hitbox,
+            range : definition.get_parameter("range", 150.0),
+            fire_rate : definition.get_parameter("fire_rate", 0.5),
+            damage : definition.get_parameter("damage", 1.0),
+            sound : definition.get_resource_location_parameter("sound", ResourceLocation::new("game", "sounds/entity/turret/turret_gunshot.ogg")),
+            animator : Animator::from_definition(game, definition),
             timer : 0.0
         };
         let ret = Box::new(Mutex::new(entity));
-        game.entities.push(ret);
+        game.entities.insert(ret)
     }
 }
 
@@ -70,19 +94,17 @@ impl Entity for Turret {
         self.health += amount
     }
 
-    fn tick(&mut self, delta: f32) {
+    fn tick(&mut self, delta: f32, ctx: &mut Context) {
+        self.animator.tick(delta);
 
-        let game = unsafe { &mut *self.game };
-        let mut player : &mut dyn Entity;
-        if game.get_player().is_some() {
-            player = game.get_player().unwrap().get_mut().unwrap()
-        }
-        else {
-            return;
-        }
+        let player = match ctx.get_player() {
+            Some(player) => { player }
+            None => { return }
+        };
+        let mut player = player.lock().unwrap();
         let dist = f32::sqrt((player.get_coords().0 - self.coords.0)*(player.get_coords().0 - self.coords.0) + ((player.get_coords().1 - self.coords.1) * (player.get_coords().1 - self.coords.1)));
 
-        if player.get_coords().1 >= self.coords.1 && dist < 150.0 {
+        if player.get_coords().1 >= self.coords.1 && dist < self.range {
             let angle= f32::atan2(player.get_coords().1 - self.coords.1, player.get_coords().0 - self.coords.0);
             // 0 -> (1/12 * PI)
             if (0.0..0.263).contains(&angle) {
@@ -115,10 +137,11 @@ impl Entity for Turret {
             /// Here there is a check to see if the timer has exceeded 5 seconds.
             /// The reason why it doesn't say "``self.timer == 5.0``" is because the timer is a sum of the time in seconds between frames.
             /// This means it could be that the timer never actually equals 5 seconds, but by using the greater than operator, the moment 5 seconds has passed, the operation is run.
-            if self.timer > 0.5 {
+            if self.timer > self.fire_rate {
                 self.timer = 0.0;
-                let _ = player.change_health(-1.0);
-                game.play_sound(ResourceLocation::new("game", "sounds/entity/turret/turret_gunshot.ogg"))
+                ctx.push(Command::ChangeHealth(player.get_uuid(), -self.damage));
+                ctx.push(Command::PlaySound(self.sound.clone()));
+                self.animator.play("fire");
             }
         }
     }
@@ -127,8 +150,8 @@ impl Entity for Turret {
         &self.resource_location
     }
 
-    fn get_index(&self) -> usize {
-        todo!()
+    fn get_uuid(&self) -> Uuid {
+        self.uuid
     }
 
     fn get_velocity(&mut self) -> (f32, f32) {
@@ -137,28 +160,22 @@ impl Entity for Turret {
 
     fn set_velocity(&mut self, velocity: (f32, f32)) {}
 
+    fn get_hitbox_size(&self) -> (f32, f32) {
+        self.hitbox
+    }
+
     fn get_asset_data(&mut self) -> AssetData {
-        match self.facing {
-            Facing::E => {
-                self.asset_data.resource_location = ResourceLocation::new("game", "entity/turret/turret_e.png")
-            }
-            Facing::SE => {
-                self.asset_data.resource_location = ResourceLocation::new("game", "entity/turret/turret_se.png")
-            }
-            Facing::S => {
-                self.asset_data.resource_location = ResourceLocation::new("game", "entity/turret/turret_s.png")
-            }
-            Facing::SW => {
-                self.asset_data.resource_location = ResourceLocation::new("game", "entity/turret/turret_sw.png")
-            }
-            Facing::W => {
-                self.asset_data.resource_location = ResourceLocation::new("game", "entity/turret/turret_w.png")
+        if self.animator.current() == Some("fire") && !self.animator.is_finished() {
+            if let Some(asset_data) = self.animator.get_asset_data() {
+                return asset_data
             }
         }
+        self.asset_data.resource_location = self.facing_textures[self.facing as usize].clone();
         self.asset_data.clone()
     }
 }
 
+#[derive(Clone, Copy)]
 enum Facing {
     E,
     SE,
@@ -4,10 +4,13 @@ use std::sync::{Arc, Mutex};
 use log::warn;
 use sdl2::event::EventPollIterator;
 use sdl2::rect::Rect;
+use uuid::Uuid;
+use crate::camera::Camera;
+use crate::collision::Aabb;
+use crate::context::Context;
 use crate::entities::player::Player;
 use crate::entities::turret::Turret;
 use crate::game::Game;
-use crate::render;
 use crate::render::{AssetData};
 use crate::resource_location::ResourceLocation;
 
@@ -34,8 +37,14 @@ pub trait Entity {
     /// changes the entity's health by a given amount, positive or negative
     /// # Example
     /// ```
-    /// let mut player = game.get_player()
-    /// player.change_health(-15f32);
+    /// # use nea_project::game::Game;
+    /// # use nea_project::loader;
+    /// # use nea_project::resource_location::ResourceLocation;
+    /// # let mut game = Game::initiate();
+    /// # game.entity_definitions = loader::load_entities("./data/");
+    /// # game.spawn(&ResourceLocation::new("game", "entity/player"), (0.0, 0.0));
+    /// let player = game.get_player().unwrap();
+    /// player.lock().unwrap().change_health(-15f32);
     /// ```
     #[must_use]
     fn change_health(&mut self, amount : f32);
@@ -44,15 +53,18 @@ pub trait Entity {
 
     }
 
-    /// Implement this function to add extra functionality to an entity, a good example in [`Turret`]
-    fn tick(&mut self, delta : f32) {}
+    /// Implement this function to add extra functionality to an entity, a good example in [`Turret`]. Changes to anything
+    /// other than the entity itself are made through the [`Context`]
+    fn tick(&mut self, delta : f32, ctx : &mut Context) {}
 
     #[must_use]
     fn get_resource_location(&self) -> &ResourceLocation;
 
-    /// returns the entity's position in the entity list
+    /// returns the entity's Uuid, which is used to find it in the [`EntityList`]
+    ///
+    /// [`EntityList`]: crate::entity_list::EntityList
     #[must_use]
-    fn get_index(&self) -> usize;
+    fn get_uuid(&self) -> Uuid;
 
     /// gets the entity's velocity
     #[must_use]
@@ -62,15 +74,28 @@ pub trait Entity {
     #[must_use]
     fn set_velocity(&mut self, velocity : (f32, f32));
 
+    /// The width and height of the entity's hitbox
+    fn get_hitbox_size(&self) -> (f32, f32) {
+        (8.0, 8.0)
+    }
+
+    /// The entity's hitbox in world space, centred on its coordinates
+    fn get_hitbox(&mut self) -> Aabb {
+        Aabb::centred(self.get_coords(), self.get_hitbox_size())
+    }
+
+    /// Called after the physics loop for every entity whose hitbox overlaps this one, given its Uuid
+    fn on_collide(&mut self, other : Uuid, ctx : &mut Context) {}
+
 
 
     /// Applies the velocity to an entity every frame. Create an implementation for [`tick`] to add additional functionality.
     ///
     /// [`tick`]: Entity::tick
-    fn physics(&mut self, delta : f32) {
+    fn physics(&mut self, delta : f32, ctx : &mut Context) {
 
         // run any additional code
-        self.tick(delta);
+        self.tick(delta, ctx);
 
         // if the current entity doesn't move, skip this
         if !self.is_static()
@@ -83,24 +108,9 @@ pub trait Entity {
 
     }
 
-    /// converts world space coordinates to screen coordinates.
-    fn screen(&mut self, player_coords :  (f32, f32)) -> (i32, i32) {
-
-        let dims = render::get_actual_dimensions().lock().unwrap().get();
-
-        let x = self.get_coords().0;
-        let y = self.get_coords().1;
-        let px = player_coords.0 as i32;
-        let py = player_coords.1 as i32;
-        let half_x = (dims.0 / 2) as i32;
-        let half_y = (dims.1 / 2) as i32;
-
-        // if the player coordinates are negative, then the rounding would be incorrect, as values such as -1.4 would round to -1, and not -2 as desired for the rendering to be correct.
-        //let px = (if player_coords.0 < 0.0 {player_coords.0 - 1.0} else { player_coords.0 }) as i32;
-        //let py = (if player_coords.1 < 0.0 {player_coords.1 - 1.0} else { player_coords.1 }) as i32;
-
-        // the conversion for the different coordinate spaces
-        (((half_x - px) as f32 + x) as i32, ((half_y - py ) as f32 + y) as i32)
+    /// converts world space coordinates to screen coordinates, see [`Camera::world_to_screen`]
+    fn screen(&mut self, camera : &Camera) -> (i32, i32) {
+        camera.world_to_screen(self.get_coords())
     }
 
     /// Returns the asset data of the entity
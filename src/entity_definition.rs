@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use log::warn;
+use sdl2::rect::Rect;
+use crate::render::AssetData;
+use crate::resource_location::ResourceLocation;
+
+/// The code an entity runs, which decides what type of entity is created when it is spawned
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Behaviour {
+    Player,
+    Turret,
+    FloatyBomb,
+    Explosion,
+    /// Does nothing besides existing
+    Idle
+}
+
+impl Behaviour {
+
+    /// Parse a behaviour from a string - defaults to idle
+    pub fn parse(val : &str) -> Self {
+        match val.to_lowercase().as_str() {
+            "player" => {Self::Player}
+            "turret" => {Self::Turret}
+            "floaty_bomb" => {Self::FloatyBomb}
+            "explosion" => {Self::Explosion}
+            "idle" => {Self::Idle}
+            _ => {
+                warn!("Behaviour {} could not be found!", val);
+                Self::Idle
+            }
+        }
+    }
+}
+
+/// Everything needed to spawn an entity, loaded from a JSON file in `data/<namespace>/entities/`. Entities are spawned from
+/// their definition by [`Game::spawn`]. The file format is:
+/// ```json
+/// {
+///   "name" : "Turret",
+///   "behaviour" : "turret",
+///   "asset_data" : {
+///     "resource_location" : "game:entity/turret/turret_se.png",
+///     "uv" : { "x" : 0, "y" : 0, "w" : 32, "h" : 32 },
+///     "origin" : { "x" : 16, "y" : 22 }
+///   },
+///   "health" : 15,
+///   "speed" : 0,
+///   "hitbox" : { "w" : 8, "h" : 8 },
+///   "animations" : { "fire" : "game:entity/turret/fire.anim.json" },
+///   "parameters" : { "range" : 150 }
+/// }
+/// ```
+/// `health`, `speed`, `hitbox`, `animations` and `parameters` are optional. `animations` names the [`Animation`]s played
+/// by the entity, such as `idle`, `walk` or `fire`, and which are used depends on its behaviour. The parameters are different for each [`Behaviour`], and
+/// fall back to the defaults in the behaviour's code when missing.
+///
+/// [`Game::spawn`]: crate::game::Game::spawn
+/// [`Animation`]: crate::animation::Animation
+#[derive(Clone)]
+pub struct EntityDefinition {
+    pub name : String,
+    /// The resource location entities spawned from this definition have, such as `game:entity/turret`
+    pub resource_location : ResourceLocation,
+    pub behaviour : Behaviour,
+    pub asset_data : AssetData,
+    pub health : f32,
+    /// How fast the entity moves, in pixels per second
+    pub speed : f32,
+    /// The width and height of the entity's hitbox
+    pub hitbox : (f32, f32),
+    /// The resource location of each of the entity's animations, by name
+    pub animations : HashMap<String, ResourceLocation>,
+    // the raw JSON of the "parameters" object
+    parameters : String
+}
+
+impl EntityDefinition {
+
+    /// Parse a definition from the contents of its JSON file, given the resource location it is registered under
+    pub fn parse(resource_location : ResourceLocation, json : &str) -> Result<Self, String> {
+        if !gjson::valid(json) {
+            return Err(format!("Entity {} is not valid JSON!", resource_location.to_string()))
+        }
+
+        for field in ["behaviour", "asset_data.resource_location", "asset_data.uv", "asset_data.origin"] {
+            if !gjson::get(json, field).exists() {
+                return Err(format!("Entity {} is missing \"{}\"!", resource_location.to_string(), field))
+            }
+        }
+
+        let asset_data = AssetData {
+            uv : Some(Rect::new(
+                gjson::get(json, "asset_data.uv.x").i32(),
+                gjson::get(json, "asset_data.uv.y").i32(),
+                gjson::get(json, "asset_data.uv.w").u32(),
+                gjson::get(json, "asset_data.uv.h").u32()
+            )),
+            origin : (
+                gjson::get(json, "asset_data.origin.x").i32(),
+                gjson::get(json, "asset_data.origin.y").i32()
+            ),
+            resource_location : ResourceLocation::parse(gjson::get(json, "asset_data.resource_location").to_string())
+        };
+
+        let hitbox = gjson::get(json, "hitbox");
+        let hitbox = if hitbox.exists() {
+            (hitbox.get("w").f32(), hitbox.get("h").f32())
+        } else {
+            (8.0, 8.0)
+        };
+
+        let mut animations = HashMap::new();
+        gjson::get(json, "animations").each(|name, rl| {
+            animations.insert(name.to_string(), ResourceLocation::parse(rl.to_string()));
+            true
+        });
+
+        let health = gjson::get(json, "health");
+        let parameters = gjson::get(json, "parameters");
+
+        Ok(Self {
+            name : gjson::get(json, "name").to_string(),
+            resource_location,
+            behaviour : Behaviour::parse(gjson::get(json, "behaviour").str()),
+            asset_data,
+            health : if health.exists() { health.f32() } else { 1.0 },
+            speed : gjson::get(json, "speed").f32(),
+            hitbox,
+            animations,
+            parameters : if parameters.exists() { parameters.json().to_string() } else { String::from("{}") }
+        })
+    }
+
+    /// Get a number from the definition's parameters, or the default if it isn't there
+    pub fn get_parameter(&self, key : &str, default : f32) -> f32 {
+        let value = gjson::get(&self.parameters, key);
+        if value.exists() { value.f32() } else { default }
+    }
+
+    /// Get a resource location from the definition's parameters, or the default if it isn't there
+    pub fn get_resource_location_parameter(&self, key : &str, default : ResourceLocation) -> ResourceLocation {
+        let value = gjson::get(&self.parameters, key);
+        if value.exists() { ResourceLocation::parse(value.to_string()) } else { default }
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+use crate::entity::Entity;
+
+/// Holds every entity in a game instance, keyed by the entity's [`Uuid`]. Unlike a position in a list, an entity's Uuid
+/// never changes, so it can be stored and used to find the entity again later, even after other entities are removed.
+///
+/// Entities are removed with [`EntityList::despawn`], which waits until [`EntityList::apply_despawns`] is called by the
+/// game after the physics pass, so an entity can safely remove itself or others during its tick.
+pub struct EntityList {
+    entities : HashMap<Uuid, Arc<Mutex<dyn Entity>>>,
+    // the order the entities were spawned in, so iterating over them is always in the same order
+    order : Vec<Uuid>,
+    despawn_queue : Vec<Uuid>
+}
+
+impl EntityList {
+
+    /// Create an empty entity list
+    pub fn new() -> Self {
+        Self {
+            entities : HashMap::new(),
+            order : vec![],
+            despawn_queue : vec![]
+        }
+    }
+
+    /// Add an entity to the list, returning its Uuid
+    pub fn insert(&mut self, entity : Box<Mutex<dyn Entity>>) -> Uuid {
+        let uuid = entity.lock().unwrap().get_uuid();
+        self.entities.insert(uuid, Arc::from(entity));
+        self.order.push(uuid);
+        uuid
+    }
+
+    /// Get an entity given its Uuid. The entity is shared, so it stays valid even if it is despawned whilst being used
+    pub fn get(&self, uuid : &Uuid) -> Option<Arc<Mutex<dyn Entity>>> {
+        self.entities.get(uuid).cloned()
+    }
+
+    /// Whether an entity is in the list, and isn't waiting to be despawned
+    pub fn contains(&self, uuid : &Uuid) -> bool {
+        self.entities.contains_key(uuid) && !self.despawn_queue.contains(uuid)
+    }
+
+    /// Mark an entity to be removed at the end of the physics pass
+    pub fn despawn(&mut self, uuid : Uuid) {
+        if !self.despawn_queue.contains(&uuid) {
+            self.despawn_queue.push(uuid);
+        }
+    }
+
+    /// Remove every entity marked by [`EntityList::despawn`]
+    pub fn apply_despawns(&mut self) {
+        for uuid in self.despawn_queue.drain(..) {
+            self.entities.remove(&uuid);
+            self.order.retain(|u| *u != uuid);
+        }
+    }
+
+    /// Remove every entity immediately. Must not be called during the physics pass
+    pub fn clear(&mut self) {
+        self.entities.clear();
+        self.order.clear();
+        self.despawn_queue.clear();
+    }
+
+    /// The Uuid of every entity, in the order they were spawned
+    pub fn uuids(&self) -> Vec<Uuid> {
+        self.order.clone()
+    }
+
+    /// Every entity along with its Uuid, in the order they were spawned
+    pub fn iter(&self) -> impl Iterator<Item = (Uuid, Arc<Mutex<dyn Entity>>)> + '_ {
+        self.order.iter().map(|uuid| (*uuid, self.entities.get(uuid).unwrap().clone()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+}
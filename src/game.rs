@@ -2,46 +2,95 @@ use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufReader;
 use std::ops::DerefMut;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use chrono::Month;
+use uuid::Uuid;
 use log::{info, warn};
 use sdl2::event::Event;
 use sdl2::keyboard::{Keycode, Scancode};
+use sdl2::controller::Button;
 use sdl2::mouse::MouseButton;
-use sdl2::render::{Texture, WindowCanvas};
+use crate::render_target::RenderTarget;
 use crate::entities::{enemy, player, turret};
+use crate::collision::Aabb;
+use crate::camera::Camera;
+use crate::command::{Command, CommandQueue};
+use crate::context::Context;
 use crate::entity::{Entity};
+use crate::animation::Animation;
+use crate::entity_definition::{Behaviour, EntityDefinition};
+use crate::entity_list::EntityList;
+use crate::gamepad::Gamepad;
+use crate::input::{Action, Binding, Keybindings};
 use crate::level::{Level, TileGraph};
 use crate::{entities, render, sound};
+use crate::entities::explosion::Explosion;
 use crate::entities::floaty_bomb::FloatyBomb;
 use crate::render::draw_pp_texture;
 use crate::resource_location::ResourceLocation;
 use crate::screen::Screen;
+use crate::screens::you_died::DeathScreen;
 use crate::sound::{AudioManager, Sound};
+use crate::spatial;
+use crate::spatial::{SpatialEntry, SpatialGrid};
 use crate::tile::{Tile, TileSize};
 use crate::utils::order_sort;
 use crate::widget::Widget;
 use crate::widgets::enum_widget::WidgetEnum;
 
+/// The movement actions, which also move between widgets on a screen, and which way they move
+const NAVIGATION : [(Action, (i32, i32)); 4] = [
+    (Action::MoveUp, (0, -1)),
+    (Action::MoveDown, (0, 1)),
+    (Action::MoveLeft, (-1, 0)),
+    (Action::MoveRight, (1, 0))
+];
+
 /// An object that manages a game instance. It holds all game data and manages the render, physics and screen loops
 pub struct Game {
-    pub entities: Vec<Box<Mutex<dyn Entity>>>, // new (uses traits) (better)
-    pub player : Option<usize>,
+    pub entities: EntityList,
+    pub player : Option<Uuid>,
     pub events: Vec<Event>,
     pub held_keys : Vec<Scancode>,
     pub running : bool,
     pub current_level : Option<Level>,
     pub current_screen : Option<Box<dyn Screen>>,
     pub tiles :  HashMap<String, Tile>,
+    /// Every entity that can be spawned, by resource location, see [`Game::spawn`]
+    pub entity_definitions : HashMap<String, EntityDefinition>,
     pub sounds : HashMap<String, Sound>,
+    /// Every animation loaded from the assets directory, see [`Animator`]
+    ///
+    /// [`Animator`]: crate::animation::Animator
+    pub animations : HashMap<String, Animation>,
     pub draw_mouse : bool,
     pub sf : i32,
     pub use_finger : bool,
     pub dims : (u32,u32),
     pub score : f32,
+    /// Changes requested by entities and widgets, applied by [`Game::apply_commands`]
+    pub commands : CommandQueue,
     debug : bool,
     audio_manager: AudioManager,
     pub dyslexia_mode: DyslexiaMode,
+    spatial : SpatialGrid,
+    /// What part of the world is drawn on screen, which follows the player
+    pub camera : Camera,
+    /// How far between the last two ticks the game is drawn, from 0 to 1. Set before each frame is rendered, see
+    /// [`FixedTimestep::alpha`]
+    ///
+    /// [`FixedTimestep::alpha`]: crate::timestep::FixedTimestep::alpha
+    pub interpolation : f32,
+    /// Which keys do each action
+    pub keybindings : Keybindings,
+    /// Where the keybindings are saved when they are changed, or None to not save them
+    pub keybindings_path : Option<String>,
+    /// The state of the game controllers, updated from [`Game::events`] at the start of every cycle
+    pub gamepad : Gamepad,
+    // where each entity was before the last tick
+    previous_coords : HashMap<Uuid, (f32, f32)>,
+    // where the mouse was last cycle, to tell when it moves
+    last_mouse : (u32, u32),
 }
 
 impl Game {
@@ -49,97 +98,346 @@ impl Game {
     /// Physics and inputs
     pub fn cycle(&mut self, delta : f32, mousex : u32, mousey : u32, dims : (u32, u32)) {
 
+        // remember where everything was, so frames can be drawn between this tick and the last
+        self.previous_coords.clear();
+        for uuid in self.entities.uuids() {
+            let coords = self.entities.get(&uuid).unwrap().lock().unwrap().get_coords();
+            self.previous_coords.insert(uuid, coords);
+        }
+        self.camera.begin_tick();
+
+        // keep up with the controllers before anything reads them, noting any presses that move between widgets
+        let navigation = self.events.iter().filter_map(|e| self.gamepad.update(e)).collect::<Vec<_>>();
 
         if !self.entities.is_empty() {
             self.score += delta;
         }
 
-        // Run physics for every entity
-        for entity in self.entities.iter() {
-            entity.lock().unwrap().physics(delta)
+        // keep the flow field pointing at the player, for enemies to follow
+        if self.player.is_some() && self.current_level.is_some() {
+            if let Some(player) = self.get_player() {
+                let player_coords = player.lock().unwrap().get_coords();
+                self.current_level.as_mut().unwrap().update_flow_field(player_coords);
+            }
+        }
+
+        // so entities can find each other during their tick
+        self.update_spatial();
+
+        // Run physics for every entity. Spawns and despawns are commands, so they aren't applied until after the loop
+        let mut ctx = self.context();
+        for uuid in ctx.entities.uuids() {
+            let entity = ctx.entities.get(&uuid).unwrap();
+            entity.lock().unwrap().physics(delta, &mut ctx);
+        }
+
+        // tell entities what they have collided with, from where they have moved to
+        self.update_spatial();
+        let pairs = self.spatial.overlapping_pairs();
+        let mut ctx = self.context();
+        for (a, b) in pairs {
+            if let (Some(entity_a), Some(entity_b)) = (ctx.entities.get(&a), ctx.entities.get(&b)) {
+                entity_a.lock().unwrap().on_collide(b, &mut ctx);
+                entity_b.lock().unwrap().on_collide(a, &mut ctx);
+            }
+        }
+
+        // now nothing is iterating through the entities, apply what they asked for during their tick
+        self.apply_commands();
+
+        // follow the player from where it has moved to
+        if let Some(player) = self.get_player() {
+            let player_coords = player.lock().unwrap().get_coords();
+            self.camera.follow(player_coords, delta);
         }
+        self.camera.update(delta);
+
+        // the screen is taken out of the game whilst it runs, so it can be given the context
+        let mut screen = self.current_screen.take();
+
+        // a screen listening for a key, such as to rebind it, gets the key press to itself
+        let captured = screen.as_mut().is_some_and(|s| s.captures_keys());
 
         // if there is a current screen, run its cycle function
-        let _ = if self.current_screen.is_some() {
-            self.current_screen.as_mut().unwrap().cycle(mousex, mousey, self.events.clone())
-        };
+        if let Some(screen) = screen.as_mut() {
+            screen.cycle(mousex, mousey, &mut self.context());
+
+            // the mouse takes over from the keyboard and controller as soon as it is moved
+            if (mousex, mousey) != self.last_mouse || !screen.uses_focus() {
+                *screen.get_focus() = None
+            }
+            if !captured && screen.uses_focus() {
+                for event in self.events.iter() {
+                    // Ctrl is held for shortcuts, such as Ctrl+S, not for moving between widgets
+                    if Binding::from_event(event).is_some_and(|b| b.ctrl) {
+                        continue
+                    }
+                    for (action, step) in NAVIGATION {
+                        if self.keybindings.is_pressed(action, event) {
+                            screen.move_focus(step)
+                        }
+                    }
+                    if self.keybindings.is_pressed(Action::NextWidget, event) {
+                        screen.focus_next()
+                    }
+                }
+            }
+            if screen.uses_focus() {
+                for step in navigation {
+                    screen.move_focus(step)
+                }
+                screen.show_focus();
+            }
+
+            // use finger mouse pointer for increased visual indication of a button
+            self.use_finger = screen.get_widgets().iter_mut().flatten().any(|w| w.get_selected());
+        }
+
+        self.last_mouse = (mousex, mousey);
 
         // handle user inputs
         for event in self.events.clone() {
-            match event {
-                Event::Quit { .. }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Escape),
-                    ..
-                } => {
-                    info!("Quitting game!");
-                    // close game on Escape, or window closure
-                    self.running=false
-                },
-                Event::KeyDown {
-                    keycode: Some(Keycode::F3),
-                    ..
-                } => {
-                    // debug mode
-                    self.debug= !self.debug
-                },
-                Event::MouseButtonDown {
-                    mouse_btn : MouseButton::Left,
-                    ..
-                } => {
-                    // on left click, check if the mouse is over a widget, if so, execute its on_click function
-                    if self.current_screen.is_some() {
-                        for wl in self.current_screen.as_mut().unwrap().get_widgets() {
-                            for w in wl {
-                                if w.get_selected() {
-                                    w.on_click()
-                                }
+            let pressed = |action| !captured && self.keybindings.is_pressed(action, &event);
+            if matches!(event, Event::Quit { .. }) || pressed(Action::Quit) {
+                info!("Quitting game!");
+                // close game on Escape, or window closure
+                self.running=false
+            }
+            else if pressed(Action::ToggleDebug) {
+                // debug mode
+                self.debug= !self.debug
+            }
+            // on left click, confirm or A, check if the mouse is over a widget, if so, execute its on_click function
+            else if matches!(event, Event::MouseButtonDown { mouse_btn : MouseButton::Left, .. }) || pressed(Action::Confirm)
+                || Gamepad::is_pressed(Button::A, &event) {
+                if let Some(screen) = screen.as_mut() {
+                    let mut ctx = self.context();
+                    for wl in screen.get_widgets() {
+                        for w in wl {
+                            if w.get_selected() {
+                                w.on_click(&mut ctx)
                             }
                         }
                     }
                 }
+            }
+        }
+
+        // put the screen back before anything the screen or its widgets asked for is applied, as it may be replaced
+        self.current_screen = screen;
+        self.apply_commands();
 
-                _ => {
-                    // do nothing
+    }
+
+    /// Applies every [`Command`] in the queue, in the order they were pushed. Must only be called when nothing is iterating
+    /// through the entities or the current screen
+    pub fn apply_commands(&mut self) {
+        let mut game_over = false;
+
+        // applying a command can queue up more, such as a spawned entity playing a sound
+        while !self.commands.is_empty() {
+            for command in self.commands.take() {
+                match command {
+                    Command::Spawn(rl, coords) => {
+                        self.spawn(&rl, coords);
+                    }
+                    Command::Despawn(uuid) => {
+                        self.entities.despawn(uuid)
+                    }
+                    Command::ChangeHealth(uuid, amount) => {
+                        if let Some(entity) = self.entities.get(&uuid) {
+                            let _ = entity.lock().unwrap().change_health(amount);
+                        }
+                    }
+                    Command::PlaySound(rl) => {
+                        self.play_sound(rl)
+                    }
+                    Command::ChangeScreen(create) => {
+                        self.current_screen = Some(create(self))
+                    }
+                    Command::SetTile(size, coords, tile) => {
+                        if let Some(level) = self.current_level.as_mut() {
+                            level.get_graph(size).remove(coords);
+                            if let Some(tile) = tile {
+                                level.get_graph(size).append(tile, coords, vec![]);
+                            }
+                            level.build_nav();
+                        }
+                    }
+                    Command::SetLevel(level) => {
+                        self.current_level = Some(level)
+                    }
+                    Command::SaveLevel(path) => {
+                        match self.current_level.as_ref() {
+                            Some(level) => {
+                                match level.save(path.as_str()) {
+                                    Ok(_) => { info!("Saved level to {}", path) }
+                                    Err(e) => { warn!("{}", e) }
+                                }
+                            }
+                            None => { warn!("No level to save to {}!", path) }
+                        }
+                    }
+                    Command::SetDyslexiaMode(mode) => {
+                        self.dyslexia_mode = mode
+                    }
+                    Command::Rebind(action, index, binding) => {
+                        info!("Binding {} to {}", action.get_name(), binding.get_name());
+                        self.keybindings.bind(action, index, binding);
+                        if let Some(path) = &self.keybindings_path {
+                            if let Err(e) = self.keybindings.save(path) {
+                                warn!("{}", e)
+                            }
+                        }
+                    }
+                    Command::Quit => {
+                        info!("Quitting game!");
+                        self.running = false
+                    }
+                    Command::GameOver => {
+                        game_over = true
+                    }
                 }
             }
         }
 
+        self.entities.apply_despawns();
+        if self.player.is_some_and(|p| !self.entities.contains(&p)) {
+            self.player = None;
+        }
+
+        // on death display the death screen and unload the level
+        if game_over {
+            self.entities.clear();
+            self.player = None;
+            self.current_level = None;
+            self.camera.set_bounds(None);
+            self.current_screen = Some(DeathScreen::create(self));
+        }
+    }
+
+    /// The [`Context`] given to entities, screens and widgets whilst they are run
+    pub fn context(&mut self) -> Context<'_> {
+        // built here rather than through a constructor, as it takes nearly every part of the game
+        Context {
+            level : self.current_level.as_ref(),
+            entities : &self.entities,
+            player : self.player,
+            held_keys : &self.held_keys,
+            events : &self.events,
+            score : self.score,
+            dyslexia_mode : self.dyslexia_mode.clone(),
+            camera : &self.camera,
+            keybindings : &self.keybindings,
+            gamepad : &self.gamepad,
+            spatial : &self.spatial,
+            commands : &mut self.commands
+        }
+    }
+
+    /// Rebuilds the spatial grid from every entity's current hitbox
+    fn update_spatial(&mut self) {
+        self.spatial.clear();
+        for (uuid, entity) in self.entities.iter() {
+            let mut entity = entity.lock().unwrap();
+            self.spatial.insert(SpatialEntry {
+                uuid,
+                coords : entity.get_coords(),
+                hitbox : entity.get_hitbox(),
+                resource_location : entity.get_resource_location().clone()
+            });
+        }
+    }
+
+    /// Returns the Uuid of every entity whose hitbox overlaps an area. Uses the entities' positions from the start of the
+    /// physics loop, so entities spawned since then aren't included
+    pub fn overlaps(&self, area : &Aabb) -> Vec<Uuid> {
+        self.spatial.query(area).iter().map(|e| e.uuid).collect()
+    }
+
+    /// Returns the Uuid of every entity within a radius of a point, see [`Game::overlaps`]
+    pub fn within_radius(&self, coords : (f32, f32), radius : f32) -> Vec<Uuid> {
+        self.spatial.within_radius(coords, radius).iter().map(|e| e.uuid).collect()
+    }
+
+    /// Returns the Uuid of the closest entity with the given resource location, see [`Game::overlaps`]
+    pub fn nearest_of_type(&self, coords : (f32, f32), rl : &ResourceLocation) -> Option<Uuid> {
+        self.spatial.nearest_of_type(coords, rl).map(|e| e.uuid)
     }
 
     /// Returns the entity assigned as the "player", may not always be [`Player`]
     ///
     /// [`Player`]: player::Player
-    pub fn get_player(&mut self) -> Option<&mut Box<Mutex<dyn Entity>>> {
-        self.entities.get_mut(self.player.unwrap())
+    pub fn get_player(&mut self) -> Option<Arc<Mutex<dyn Entity>>> {
+        self.entities.get(&self.player?)
     }
 
-    /// Generates the demo level and loads it into the game instance. the actual level generations is found in [`Level::create_demo_level`]
+    /// Loads the demo level from `data/game/levels/demo.json`, see [`Game::load_level`]
     pub fn load_demo_level(&mut self) {
+        if let Err(e) = self.load_level("./data/game/levels/demo.json") {
+            warn!("{}", e)
+        }
+    }
 
-        // test entities
-        player::Player::create(self);
-        turret::Turret::create(self);
-        FloatyBomb::create(self, (86.0,40.0));
-        //FloatyBomb::create(self, (120.0,40.0));
-        //enemy::Enemy::create(self);
+    /// Loads a level file into the game instance, spawning the player and the level's entities. The level file format is
+    /// described @ [`Level::load`]
+    pub fn load_level(&mut self, path : &str) -> Result<(), String> {
+        let level = Level::load(path, &self.tiles)?;
 
-        let _ = self
-            .entities
-            .get_mut(0)
-            .unwrap()
-            .lock()
-            .unwrap()
-            .set_coords((16.0, 80.0));
+        // the player is always the first entity
+        self.spawn(&ResourceLocation::new("game", "entity/player"), level.player_start);
+
+        for (rl, coords) in level.entity_spawns.iter() {
+            self.spawn(rl, *coords);
+        }
+
+        // keep the camera inside the level, starting on the player
+        self.camera.set_bounds(level.get_bounds());
+        self.camera.snap_to(level.player_start);
+
+        self.current_level = Some(level);
+        Ok(())
+    }
+
+    /// Spawns an entity from its definition in [`Game::entity_definitions`], given its resource location and world space
+    /// coordinates. Returns the spawned entity's Uuid, or None if it couldn't be spawned
+    pub fn spawn(&mut self, rl : &ResourceLocation, coords : (f32, f32)) -> Option<Uuid> {
+        let definition = match self.entity_definitions.get(&rl.to_string()) {
+            Some(definition) => { definition.clone() }
+            None => {
+                warn!("Unknown entity {}!", rl.to_string());
+                return None
+            }
+        };
+
+        let uuid = match definition.behaviour {
+            Behaviour::Player => { player::Player::create(self, &definition)? }
+            Behaviour::Turret => { turret::Turret::create(self, &definition) }
+            Behaviour::FloatyBomb => { FloatyBomb::create(self, &definition) }
+            Behaviour::Explosion => { Explosion::create(self, &definition) }
+            Behaviour::Idle => { enemy::Enemy::create(self, &definition) }
+        };
 
         let _ = self
             .entities
-            .get_mut(1)
+            .get(&uuid)
             .unwrap()
             .lock()
             .unwrap()
-            .set_coords((128.0, 36.0));
-        self.current_level = Some(Level::create_demo_level(&self.tiles));
+            .set_coords(coords);
+        Some(uuid)
+    }
+
+    /// Where an entity is drawn, part of the way between where it was before the last tick and where it is now, see
+    /// [`Game::interpolation`]. Entities that were spawned during the last tick are drawn where they are
+    pub fn get_render_coords(&self, uuid : &Uuid) -> Option<(f32, f32)> {
+        let current = self.entities.get(uuid)?.lock().unwrap().get_coords();
+        let previous = match self.previous_coords.get(uuid) {
+            Some(previous) => { *previous }
+            None => { return Some(current) }
+        };
+        let t = self.interpolation.clamp(0.0, 1.0);
+        Some((previous.0 + (current.0 - previous.0) * t, previous.1 + (current.1 - previous.1) * t))
     }
 
     /// The render loop for entities, screens and the mouse. The entity rendering is done here, for specifics on other elements see the render functions for [`Screens`]|[`Levels/Tiles`]|[`Widgets`]
@@ -147,32 +445,39 @@ impl Game {
     /// [`Screens`]: Screen::render
     /// [`Levels/Tiles`]: Level::render
     /// [`Widgets`]: Widget::render
-    pub fn render(&mut self, canvas: &mut WindowCanvas, sf: i32, textures : &HashMap<String, Texture>, dims : (u32, u32), mousex : u32, mousey : u32) {
+    pub fn render(&mut self, target: &mut dyn RenderTarget, sf: i32, dims : (u32, u32), mousex : u32, mousey : u32) {
+
+        // draw the world between the last two ticks
+        self.camera.interpolate(self.interpolation);
+
+        // get the level
+        let mut level = &mut self.current_level;
+        // make sure the level isn't None, and render it to screen
+        if level.is_some() {
+            level.as_mut().unwrap().render(&self.camera, target, sf, self.debug);
+        }
 
         // if there are entities, render them to screen
         if !self.entities.is_empty() {
 
             // calculate the order the entities are rendered in - it is essential that the order of entities in the list isn't changed
-            let order = order_sort(&mut self.entities);
-
-            // get the player and its coordinates
-            let player = self.get_player().unwrap();
-            let player_coords = player.lock().unwrap().get_coords();
+            let order = order_sort(&self.entities);
+            let uuids = self.entities.uuids();
 
-            // get the level
-            let mut level = &mut self.current_level;
-            // make sure the level isn't None, and render it to screen
-            if level.is_some() {
-                level.as_mut().unwrap().render(player_coords, textures, canvas, sf, self.debug);
-            }
+            // only entities that can be seen are drawn
+            let visible = self.camera.visible_area();
 
             // iterate through the order
             for x in order {
-                let mut list = &mut self.entities;
-                let mut obj = list.get(x.1).unwrap().lock().unwrap();
-                let screen_coords = &obj.screen(player_coords);
+                let coords = self.get_render_coords(&uuids[x.1]).unwrap();
+                let entity = self.entities.get(&uuids[x.1]).unwrap();
+                let mut obj = entity.lock().unwrap();
                 let asset_data = &obj.get_asset_data();
-                draw_pp_texture(screen_coords.0, screen_coords.1, &asset_data, canvas, sf, textures);
+                if !render::texture_bounds(coords, asset_data).intersects(&visible) {
+                    continue
+                }
+                let screen_coords = self.camera.world_to_screen(coords);
+                draw_pp_texture(screen_coords.0, screen_coords.1, &asset_data, target, sf);
             }
         }
 
@@ -180,7 +485,7 @@ impl Game {
         let scrn = &mut self.current_screen;
         // make sure the screen isn't None, and render it to screen
         if scrn.is_some() {
-            scrn.as_mut().unwrap().render(textures, sf, canvas, dims, self.debug);
+            scrn.as_mut().unwrap().render(sf, target, dims, self.debug);
         }
 
         // draw the mouse, unless instructed otherwise
@@ -190,9 +495,8 @@ impl Game {
                     mousex as i32,
                     mousey as i32,
                     &render::get_icons().lock().unwrap().get("finger").unwrap(),
-                    canvas,
-                    sf,
-                    &textures
+                    target,
+                    sf
                 );
             }
             else {
@@ -200,9 +504,8 @@ impl Game {
                     mousex as i32,
                     mousey as i32,
                     &render::get_icons().lock().unwrap().get("cursor").unwrap(),
-                    canvas,
-                    sf,
-                    &textures
+                    target,
+                    sf
                 );
             }
         }
@@ -228,7 +531,7 @@ impl Game {
     pub fn initiate() -> Self {
         
         Self{
-            entities: vec![],
+            entities: EntityList::new(),
             player : None,
             events: vec![],
             held_keys : vec![],
@@ -236,15 +539,26 @@ impl Game {
             current_level : None,
             current_screen : None,
             tiles: Default::default(),
+            animations: Default::default(),
+            entity_definitions: Default::default(),
             sounds : Default::default(),
             draw_mouse : true,
             sf : 6,
             use_finger : false,
             dims: (0,0),
             score: 0.0,
+            commands : CommandQueue::new(),
             debug : false,
             audio_manager: AudioManager::create(),
-            dyslexia_mode : DyslexiaMode::OFF
+            dyslexia_mode : DyslexiaMode::OFF,
+            spatial : SpatialGrid::create(spatial::CELL_SIZE),
+            camera : Camera::new(),
+            interpolation : 1.0,
+            keybindings : Keybindings::default(),
+            keybindings_path : None,
+            gamepad : Gamepad::default(),
+            previous_coords : HashMap::new(),
+            last_mouse : (0, 0),
         }
         
     }
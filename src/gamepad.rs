@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use sdl2::controller::{Axis, Button};
+use sdl2::event::Event;
+
+/// How far a stick has to be pushed, from 0 to 1, before it moves the player. Sticks rarely rest exactly in the middle
+pub const DEADZONE : f32 = 0.25;
+
+/// How far a stick has to be pushed, from 0 to 1, to move between widgets on a screen
+pub const NAVIGATE_THRESHOLD : f32 = 0.6;
+
+/// Every axis SDL2 has for a game controller
+const AXES : [Axis; 6] = [Axis::LeftX, Axis::LeftY, Axis::RightX, Axis::RightY, Axis::TriggerLeft, Axis::TriggerRight];
+
+/// Every button SDL2 has for a game controller
+const BUTTONS : [Button; 21] = [
+    Button::A, Button::B, Button::X, Button::Y, Button::Back, Button::Guide, Button::Start, Button::LeftStick,
+    Button::RightStick, Button::LeftShoulder, Button::RightShoulder, Button::DPadUp, Button::DPadDown, Button::DPadLeft,
+    Button::DPadRight, Button::Misc1, Button::Paddle1, Button::Paddle2, Button::Paddle3, Button::Paddle4, Button::Touchpad
+];
+
+/// Get an axis from its number, or None if there isn't one with that number
+pub fn axis_from_i32(n : i32) -> Option<Axis> {
+    AXES.iter().find(|a| **a as i32 == n).copied()
+}
+
+/// Get a button from its number, or None if there isn't one with that number
+pub fn button_from_i32(n : i32) -> Option<Button> {
+    BUTTONS.iter().find(|b| **b as i32 == n).copied()
+}
+
+/// The state of a single game controller
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Controller {
+    /// How far the left stick is pushed, from -1 to 1 on each axis, right and down being positive
+    pub left_stick : (f32, f32),
+    pub held_buttons : Vec<Button>
+}
+
+/// The state of the game controllers, kept up to date from the controller events given to the game. Every controller
+/// plugged in controls the same player.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Gamepad {
+    /// Each controller that has been used, keyed by the instance id SDL2 gives it in its events
+    pub controllers : HashMap<u32, Controller>
+}
+
+impl Gamepad {
+
+    /// Update the state from an event. Returns which way to move between widgets if the D-pad has been pressed or the left
+    /// stick has just been pushed past [`NAVIGATE_THRESHOLD`], see [`Screen::navigate`]
+    ///
+    /// [`Screen::navigate`]: crate::screen::Screen::navigate
+    pub fn update(&mut self, event : &Event) -> Option<(i32, i32)> {
+        match event {
+            Event::ControllerAxisMotion { which, axis : Axis::LeftX, value, .. } => {
+                let stick = &mut self.controllers.entry(*which).or_default().left_stick;
+                let before = stick.0;
+                stick.0 = axis_value(*value);
+                flick(before, stick.0).map(|dx| (dx, 0))
+            }
+            Event::ControllerAxisMotion { which, axis : Axis::LeftY, value, .. } => {
+                let stick = &mut self.controllers.entry(*which).or_default().left_stick;
+                let before = stick.1;
+                stick.1 = axis_value(*value);
+                flick(before, stick.1).map(|dy| (0, dy))
+            }
+            Event::ControllerButtonDown { which, button, .. } => {
+                let held_buttons = &mut self.controllers.entry(*which).or_default().held_buttons;
+                if !held_buttons.contains(button) {
+                    held_buttons.push(*button)
+                }
+                match button {
+                    Button::DPadUp => { Some((0, -1)) }
+                    Button::DPadDown => { Some((0, 1)) }
+                    Button::DPadLeft => { Some((-1, 0)) }
+                    Button::DPadRight => { Some((1, 0)) }
+                    _ => { None }
+                }
+            }
+            Event::ControllerButtonUp { which, button, .. } => {
+                if let Some(controller) = self.controllers.get_mut(which) {
+                    controller.held_buttons.retain(|b| b != button);
+                }
+                None
+            }
+            // let go of everything on that controller, so the player doesn't keep walking after it is unplugged
+            Event::ControllerDeviceRemoved { which, .. } => {
+                self.controllers.remove(which);
+                None
+            }
+            _ => { None }
+        }
+    }
+
+    /// Whether a button is held down on any controller
+    pub fn is_held(&self, button : Button) -> bool {
+        self.controllers.values().any(|c| c.held_buttons.contains(&button))
+    }
+
+    /// Whether an event is a button being pressed
+    pub fn is_pressed(button : Button, event : &Event) -> bool {
+        matches!(event, Event::ControllerButtonDown { button : b, .. } if *b == button)
+    }
+
+    /// Which way the left stick moves the player, or None if it is within the [`DEADZONE`]. The further the stick is
+    /// pushed, the longer the vector, up to a length of 1. If more than one controller is plugged in, the stick pushed
+    /// furthest is used
+    pub fn movement(&self) -> Option<(f32, f32)> {
+        let length_of = |(x, y) : (f32, f32)| (x * x + y * y).sqrt();
+        let (x, y) = self.controllers.values()
+            .map(|c| c.left_stick)
+            .max_by(|a, b| length_of(*a).total_cmp(&length_of(*b)))?;
+        let length = length_of((x, y));
+        if length < DEADZONE {
+            return None
+        }
+        // start from nothing at the edge of the deadzone, rather than jumping straight to a quarter speed
+        let scale = ((length - DEADZONE) / (1.0 - DEADZONE)).min(1.0) / length;
+        Some((x * scale, y * scale))
+    }
+}
+
+/// An axis value from SDL2, from -1 to 1
+fn axis_value(value : i16) -> f32 {
+    (value as f32 / i16::MAX as f32).clamp(-1.0, 1.0)
+}
+
+// which way a stick has been pushed along an axis, if it has only just gone past the threshold
+fn flick(before : f32, after : f32) -> Option<i32> {
+    if after >= NAVIGATE_THRESHOLD && before < NAVIGATE_THRESHOLD {
+        Some(1)
+    }
+    else if after <= -NAVIGATE_THRESHOLD && before > -NAVIGATE_THRESHOLD {
+        Some(-1)
+    }
+    else {
+        None
+    }
+}
@@ -0,0 +1,221 @@
+use image::RgbaImage;
+use log::{info, warn};
+use sdl2::event::Event;
+use sdl2::keyboard::Scancode;
+use crate::atlas::AtlasLayout;
+use crate::game::Game;
+use crate::loader;
+use crate::render;
+use crate::render::TARGET_DIMENSIONS;
+use crate::render_target::{RenderTarget, SoftwareTarget};
+use crate::replay::{Recording, TickInput};
+use crate::screen::Screen;
+use crate::screens::hud_screen::HudScreen;
+use crate::timestep;
+use crate::viewport::Viewport;
+
+/// The delta used by the headless runner if none is given, the same as the game window's, see [`timestep::STEP`]
+pub const DEFAULT_DELTA : f32 = timestep::STEP;
+
+/// An input that is fed into the game at a certain tick by the [`HeadlessRunner`]
+pub enum ScriptedInput {
+    /// Push an event for a single tick, like a key press or a mouse click
+    Event(Event),
+    /// Hold a key down until it is released
+    Hold(Scancode),
+    /// Release a held key
+    Release(Scancode),
+    /// Move the mouse to a point on the screen
+    MoveMouse(u32, u32)
+}
+
+/// Runs [`Game::cycle`] without creating a window, so the simulation can be run in CI or tests. Delta is
+/// fixed, so runs given the same script will behave the same. Nothing is rendered unless [`HeadlessRunner::render`]
+/// is called.
+pub struct HeadlessRunner {
+    pub game : Game,
+    pub delta : f32,
+    tick : u32,
+    script : Vec<(u32, ScriptedInput)>,
+    held_keys : Vec<Scancode>,
+    mouse : (u32, u32),
+    recording : Option<Recording>
+}
+
+impl HeadlessRunner {
+
+    /// Creates a runner with tiles and sounds loaded from the given data and assets directories
+    pub fn create(data : &str, assets : &str, delta : f32) -> Self {
+        let mut game = Game::initiate();
+
+        game.tiles = loader::load_tiles(data);
+        game.entity_definitions = loader::load_entities(data);
+        game.sounds = loader::load_sounds(assets);
+        game.animations = loader::load_animations(assets);
+        game.dims = TARGET_DIMENSIONS;
+
+        Self {
+            game,
+            delta,
+            tick : 0,
+            script : vec![],
+            held_keys : vec![],
+            mouse : (0, 0),
+            recording : None
+        }
+    }
+
+    /// Loads the demo level along with the HUD, the same as pressing play on the main menu
+    pub fn load_demo_level(&mut self) {
+        self.game.load_demo_level();
+        self.game.current_screen = Some(HudScreen::create(&mut self.game));
+    }
+
+    /// Schedule an input to happen at a given tick
+    pub fn at(&mut self, tick : u32, input : ScriptedInput) {
+        self.script.push((tick, input));
+    }
+
+    /// Schedule an input to happen after a number of simulated seconds
+    pub fn at_time(&mut self, seconds : f32, input : ScriptedInput) {
+        let tick = (seconds / self.delta).round() as u32;
+        self.at(tick, input);
+    }
+
+    /// Run a single game cycle, applying any inputs scheduled for this tick
+    pub fn step(&mut self) {
+        let mut events = vec![];
+
+        // apply the scripted inputs in the order they were added
+        for (tick, input) in self.script.iter() {
+            if *tick != self.tick {
+                continue
+            }
+            match input {
+                ScriptedInput::Event(event) => {
+                    events.push(event.clone())
+                }
+                ScriptedInput::Hold(key) => {
+                    if !self.held_keys.contains(key) {
+                        self.held_keys.push(*key)
+                    }
+                }
+                ScriptedInput::Release(key) => {
+                    self.held_keys.retain(|k| k != key)
+                }
+                ScriptedInput::MoveMouse(x, y) => {
+                    self.mouse = (*x, *y)
+                }
+            }
+        }
+
+        self.step_with(TickInput {
+            held_keys : self.held_keys.clone(),
+            events,
+            mouse : self.mouse
+        });
+    }
+
+    /// Run a single game cycle with the given input, instead of the script
+    pub fn step_with(&mut self, input : TickInput) {
+        input.apply(&mut self.game);
+        let dims = self.game.dims;
+        self.game.cycle(self.delta, input.mouse.0, input.mouse.1, dims);
+
+        if let Some(recording) = self.recording.as_mut() {
+            recording.push(input);
+        }
+        self.tick += 1;
+    }
+
+    /// Start recording the input of every tick from now on, starting in the given level
+    pub fn start_recording(&mut self, level : Option<String>) {
+        self.recording = Some(Recording::new(self.delta, level));
+    }
+
+    /// Stop recording, returning what was recorded
+    pub fn take_recording(&mut self) -> Option<Recording> {
+        self.recording.take()
+    }
+
+    /// Creates a runner that starts in the same place as a recording, loading its level if it has one
+    pub fn from_recording(data : &str, assets : &str, recording : &Recording) -> Self {
+        let mut runner = Self::create(data, assets, recording.delta);
+        if let Some(level) = &recording.level {
+            if let Err(e) = runner.game.load_level(level) {
+                warn!("{}", e)
+            }
+            runner.game.current_screen = Some(HudScreen::create(&mut runner.game));
+        }
+        runner
+    }
+
+    /// Feed every tick of a recording back into the game, or until it stops running
+    pub fn replay(&mut self, recording : &Recording) {
+        for input in recording.ticks.iter() {
+            if !self.game.running {
+                break
+            }
+            self.step_with(input.clone());
+        }
+    }
+
+    /// Run the game for a number of simulated seconds, or until it stops running
+    pub fn run_for(&mut self, seconds : f32) {
+        let ticks = (seconds / self.delta).round() as u32;
+        for _ in 0..ticks {
+            if !self.game.running {
+                break
+            }
+            self.step();
+        }
+    }
+
+    /// Render the current frame into an image at the target resolution, with textures from an atlas layout, see
+    /// [`loader::pack_textures`]
+    pub fn render(&mut self, layout : &AtlasLayout) -> RgbaImage {
+        self.render_in(layout, Viewport::windowed(1))
+    }
+
+    /// Render the current frame into an image the size of a window, letterboxed and scaled up the same way as the game
+    /// window is
+    pub fn render_in(&mut self, layout : &AtlasLayout, viewport : Viewport) -> RgbaImage {
+        let mut target = SoftwareTarget::create(viewport.window, layout);
+        target.set_viewport(viewport);
+        render::draw_background(&mut target);
+        let dims = self.game.dims;
+        self.game.render(&mut target, viewport.sf, dims, self.mouse.0, self.mouse.1);
+        target.image
+    }
+
+    /// The amount of ticks that have been run
+    pub fn get_tick(&self) -> u32 {
+        self.tick
+    }
+
+    /// The player's health, or None if there isn't a player
+    pub fn player_health(&mut self) -> Option<f32> {
+        self.game.get_player().map(|p| p.lock().unwrap().get_health())
+    }
+
+    /// The current score
+    pub fn score(&self) -> f32 {
+        self.game.score
+    }
+
+    /// The amount of entities in the game
+    pub fn entity_count(&self) -> usize {
+        self.game.entities.len()
+    }
+
+    /// Logs the state of the game, used at the end of a headless run
+    pub fn report(&mut self) {
+        info!("Headless run finished after {} ticks ({}s)", self.tick, self.tick as f32 * self.delta);
+        match self.player_health() {
+            Some(health) => { info!("Player health : {}", health) }
+            None => { info!("Player health : no player") }
+        }
+        info!("Score : {}", self.score());
+        info!("Entities : {}", self.entity_count());
+    }
+}
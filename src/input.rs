@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use log::warn;
+use sdl2::event::Event;
+use sdl2::keyboard::{Mod, Scancode};
+
+/// Something the player can do with a key. The game asks whether an action is happening rather than checking for a
+/// certain key, so every key can be changed by the player, see [`Keybindings`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    /// Press the selected widget, the same as clicking it
+    Confirm,
+    /// Move on to the next widget on a screen, the movement keys moving between them too
+    NextWidget,
+    Quit,
+    ToggleDebug,
+    EditorUndo,
+    EditorRedo,
+    EditorSave,
+    EditorLoad,
+    /// Select the next tile in the editor's palette
+    EditorNextTile
+}
+
+impl Action {
+
+    /// Every action, in the order they are shown on the settings screen
+    pub const ALL : [Action; 13] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Confirm,
+        Action::NextWidget,
+        Action::Quit,
+        Action::ToggleDebug,
+        Action::EditorUndo,
+        Action::EditorRedo,
+        Action::EditorSave,
+        Action::EditorLoad,
+        Action::EditorNextTile
+    ];
+
+    /// The name of the action in the keybindings file
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Action::MoveUp => { "move_up" }
+            Action::MoveDown => { "move_down" }
+            Action::MoveLeft => { "move_left" }
+            Action::MoveRight => { "move_right" }
+            Action::Confirm => { "confirm" }
+            Action::NextWidget => { "next_widget" }
+            Action::Quit => { "quit" }
+            Action::ToggleDebug => { "toggle_debug" }
+            Action::EditorUndo => { "editor_undo" }
+            Action::EditorRedo => { "editor_redo" }
+            Action::EditorSave => { "editor_save" }
+            Action::EditorLoad => { "editor_load" }
+            Action::EditorNextTile => { "editor_next_tile" }
+        }
+    }
+
+    /// Get an action from its name in the keybindings file
+    pub fn parse(name : &str) -> Option<Self> {
+        Self::ALL.iter().find(|a| a.get_name() == name).copied()
+    }
+
+    /// The index of the action in [`Action::ALL`]
+    pub fn get_index(&self) -> usize {
+        Self::ALL.iter().position(|a| a == self).unwrap()
+    }
+}
+
+/// Every value SDL2 has a scancode for. [`Scancode::from_i32`] can't be given anything else
+const SCANCODES : [(i32, i32); 6] = [(0, 0), (4, 129), (133, 164), (176, 221), (224, 231), (257, 286)];
+
+/// Get a scancode from its number, or None if there isn't one with that number. Safe to use on numbers read from a file,
+/// unlike [`Scancode::from_i32`]
+pub fn scancode_from_i32(n : i32) -> Option<Scancode> {
+    if SCANCODES.iter().any(|(start, end)| (*start..=*end).contains(&n)) {
+        Scancode::from_i32(n)
+    }
+    else {
+        None
+    }
+}
+
+/// A key, and whether Ctrl has to be held with it. Keys are physical positions on the keyboard, so the default movement
+/// keys are in the same place whatever the keyboard layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Binding {
+    pub scancode : Scancode,
+    pub ctrl : bool
+}
+
+impl Binding {
+
+    /// A key on its own
+    pub fn key(scancode : Scancode) -> Self {
+        Self {
+            scancode,
+            ctrl : false
+        }
+    }
+
+    /// A key pressed whilst holding Ctrl
+    pub fn ctrl(scancode : Scancode) -> Self {
+        Self {
+            scancode,
+            ctrl : true
+        }
+    }
+
+    /// The binding a key press would make, or None if it isn't a key press. Ctrl on its own isn't a binding, as it is used
+    /// with other keys
+    pub fn from_event(event : &Event) -> Option<Self> {
+        match event {
+            Event::KeyDown { scancode : Some(scancode), keymod, .. } => {
+                if matches!(scancode, Scancode::LCtrl | Scancode::RCtrl) {
+                    return None
+                }
+                Some(Self {
+                    scancode : *scancode,
+                    ctrl : keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD)
+                })
+            }
+            _ => { None }
+        }
+    }
+
+    /// The binding as it is written in the keybindings file and shown on screen, such as `Ctrl+Z` or `Space`
+    pub fn get_name(&self) -> String {
+        let key = format!("{:?}", self.scancode);
+        if self.ctrl { format!("Ctrl+{}", key) } else { key }
+    }
+
+    /// Read a binding written by [`Binding::get_name`], ignoring case
+    pub fn parse(name : &str) -> Option<Self> {
+        let (ctrl, key) = match name.get(..5) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("ctrl+") => { (true, &name[5..]) }
+            _ => { (false, name) }
+        };
+        // scancodes are looked up by their name here rather than through SDL2, so the file can be read without it
+        let scancode = SCANCODES.iter()
+            .flat_map(|(start, end)| *start..=*end)
+            .filter_map(scancode_from_i32)
+            .find(|s| format!("{:?}", s).eq_ignore_ascii_case(key))?;
+        Some(Self {
+            scancode,
+            ctrl
+        })
+    }
+}
+
+/// Which keys do each [`Action`]. Loaded from a JSON file of action names to a list of bindings, for example
+/// `{"move_up" : ["W", "Up"], "editor_undo" : ["Ctrl+Z"]}`. Any action not in the file keeps its default keys.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keybindings {
+    bindings : HashMap<Action, Vec<Binding>>
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        let mut bindings = HashMap::new();
+        // the arrow keys move too, for anyone who would rather use their right hand
+        bindings.insert(Action::MoveUp, vec![Binding::key(Scancode::W), Binding::key(Scancode::Up)]);
+        bindings.insert(Action::MoveDown, vec![Binding::key(Scancode::S), Binding::key(Scancode::Down)]);
+        bindings.insert(Action::MoveLeft, vec![Binding::key(Scancode::A), Binding::key(Scancode::Left)]);
+        bindings.insert(Action::MoveRight, vec![Binding::key(Scancode::D), Binding::key(Scancode::Right)]);
+        bindings.insert(Action::Confirm, vec![Binding::key(Scancode::Return), Binding::key(Scancode::Space)]);
+        bindings.insert(Action::NextWidget, vec![Binding::key(Scancode::Tab)]);
+        bindings.insert(Action::Quit, vec![Binding::key(Scancode::Escape)]);
+        bindings.insert(Action::ToggleDebug, vec![Binding::key(Scancode::F3)]);
+        bindings.insert(Action::EditorUndo, vec![Binding::ctrl(Scancode::Z)]);
+        bindings.insert(Action::EditorRedo, vec![Binding::ctrl(Scancode::Y)]);
+        bindings.insert(Action::EditorSave, vec![Binding::ctrl(Scancode::S)]);
+        bindings.insert(Action::EditorLoad, vec![Binding::ctrl(Scancode::L)]);
+        bindings.insert(Action::EditorNextTile, vec![Binding::key(Scancode::B)]);
+        Self {
+            bindings
+        }
+    }
+}
+
+impl Keybindings {
+
+    /// The keys bound to an action
+    pub fn get(&self, action : Action) -> &[Binding] {
+        self.bindings.get(&action).map(|b| b.as_slice()).unwrap_or(&[])
+    }
+
+    /// Replace one of the keys bound to an action, given its position in [`Keybindings::get`]. The rest are kept, and if
+    /// there isn't a key at that position the new one is added after them
+    pub fn bind(&mut self, action : Action, index : usize, binding : Binding) {
+        let bindings = self.bindings.entry(action).or_default();
+        match bindings.get_mut(index) {
+            Some(b) => { *b = binding }
+            None => { bindings.push(binding) }
+        }
+    }
+
+    /// Whether any key bound to an action is held down. Ctrl isn't checked, as held keys are used for movement
+    pub fn is_held(&self, action : Action, held_keys : &[Scancode]) -> bool {
+        self.get(action).iter().any(|b| held_keys.contains(&b.scancode))
+    }
+
+    /// Whether an event is a key bound to an action being pressed. Bindings without Ctrl work whether or not it is held
+    pub fn is_pressed(&self, action : Action, event : &Event) -> bool {
+        match Binding::from_event(event) {
+            Some(pressed) => {
+                self.get(action).iter().any(|b| b.scancode == pressed.scancode && (!b.ctrl || pressed.ctrl))
+            }
+            None => { false }
+        }
+    }
+
+    /// Loads keybindings from a JSON file written by [`Keybindings::save`]. Actions or keys that can't be read are
+    /// skipped, keeping their defaults
+    pub fn load(path : &str) -> Result<Self, String> {
+
+        let json = fs::read_to_string(path).map_err(|e| format!("Could not read keybindings {} : {}", path, e))?;
+
+        if !gjson::valid(json.as_str()) {
+            return Err(format!("Keybindings {} is not valid JSON!", path))
+        }
+
+        let mut keybindings = Self::default();
+        gjson::parse(json.as_str()).each(|name, keys| {
+            match Action::parse(name.str()) {
+                Some(action) => {
+                    let bindings = keys.array().iter().filter_map(|k| Binding::parse(k.str())).collect::<Vec<_>>();
+                    if !bindings.is_empty() {
+                        keybindings.bindings.insert(action, bindings);
+                    }
+                }
+                None => {
+                    warn!("Unknown action {} in {}!", name.str(), path)
+                }
+            }
+            true
+        });
+
+        Ok(keybindings)
+    }
+
+    /// Saves the keybindings to a JSON file that can be read by [`Keybindings::load`]
+    pub fn save(&self, path : &str) -> Result<(), String> {
+
+        let actions = Action::ALL.iter().map(|action| {
+            let keys = self.get(*action).iter()
+                .map(|b| gjson::tools::escape(b.get_name().as_str()))
+                .collect::<Vec<_>>();
+            format!("\"{}\":[{}]", action.get_name(), keys.join(","))
+        }).collect::<Vec<_>>();
+
+        let json = format!("{{{}}}", actions.join(","));
+
+        // make sure the folder exists before writing to it
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Could not create {} : {}", parent.display(), e))?;
+        }
+
+        fs::write(path, gjson::tools::pretty(json.as_str())).map_err(|e| format!("Could not save keybindings {} : {}", path, e))
+    }
+}
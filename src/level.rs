@@ -1,8 +1,14 @@
-use std::cmp::PartialEq;
-use std::collections::HashMap;
+use std::cmp::{PartialEq, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
 use image::imageops::tile;
 use log::warn;
-use sdl2::render::{Canvas, Texture, WindowCanvas};
+use sdl2::render::Canvas;
+use crate::render_target::RenderTarget;
+use crate::camera::Camera;
+use crate::collision::Aabb;
+use crate::render;
 use crate::game::Game;
 use crate::resource_location::ResourceLocation;
 use crate::tile::{Tile, TileSize, TileType};
@@ -12,10 +18,19 @@ use crate::utils::get_dist;
 ///
 /// [`TileGraphs`]: TileGraph
 pub struct Level {
-    pub(crate) tile_big : TileGraph,
-    pub(crate) tile_medium : TileGraph,
-    pub(crate) tile_small : TileGraph,
-    pub(crate) tile_nav : TileGraph
+    pub tile_big : TileGraph,
+    pub tile_medium : TileGraph,
+    pub tile_small : TileGraph,
+    pub tile_nav : TileGraph,
+    /// Where the player is spawned, in world space
+    pub player_start : (f32, f32),
+    /// The entities spawned when the level is loaded, by resource location and world space coordinates
+    pub entity_spawns : Vec<(ResourceLocation, (f32, f32))>,
+    /// Whether enemies can path-find diagonally through the level
+    pub diagonal_nav : bool,
+    // the nav tile the flow field leads to, and the cost of reaching it from every other nav tile
+    flow_target : Option<(i32, i32)>,
+    flow_field : HashMap<(i32, i32), u32>
 }
 
 impl Level {
@@ -23,15 +38,22 @@ impl Level {
     /// Calls the [`render`] function on all the tile graphs
     ///
     /// [`render`]: TileGraph::render
-    pub fn render(&mut self, player_coords :  (f32, f32), texture : &HashMap<String, Texture>, canvas: &mut WindowCanvas, sf : i32, debug : bool) {
-        self.tile_big.render(player_coords, texture, canvas, sf);
-        self.tile_medium.render(player_coords, texture, canvas, sf);
-        self.tile_small.render(player_coords, texture, canvas, sf);
+    pub fn render(&mut self, camera : &Camera, target : &mut dyn RenderTarget, sf : i32, debug : bool) {
+        self.tile_big.render(camera, target, sf);
+        self.tile_medium.render(camera, target, sf);
+        self.tile_small.render(camera, target, sf);
         if debug {
-            self.tile_nav.render(player_coords, texture, canvas, sf)
+            self.tile_nav.render(camera, target, sf)
         }
     }
 
+    /// The area of the world covered by the level's tiles, or None if it has none. The nav tiles aren't included
+    pub fn get_bounds(&self) -> Option<Aabb> {
+        [&self.tile_big, &self.tile_medium, &self.tile_small].iter()
+            .filter_map(|graph| graph.get_bounds())
+            .reduce(|a, b| a.union(&b))
+    }
+
     /// Returns the tile at a given world space coordinate and tile size, see more @ [`TileGraph::get_tile`]
     pub fn get_tile(&mut self, size : TileSize, coordinates: (f32, f32)) -> Tile {
 
@@ -50,56 +72,288 @@ impl Level {
 
     }
 
-    /// Creates the level for the demo, entities are loaded @ [`Game::load_demo_level`]
-    pub fn create_demo_level(tiles : &HashMap<String, Tile>) -> Self {
-
-        // get tiles from tile map
-        let wall = (tiles.get("game:tiles/wall.json").unwrap().clone());
-        let floor = (tiles.get("game:tiles/floor.json").unwrap().clone());
-
-        // create level
-        let mut level = Self{
+    /// Creates a level with no tiles or entities
+    pub fn create_empty() -> Self {
+        Self{
             tile_big: TileGraph::create(TileSize::BIG),
             tile_medium: TileGraph::create(TileSize::MEDIUM),
             tile_small: TileGraph::create(TileSize::SMALL),
             tile_nav: TileGraph::create(TileSize::SMALL),
-        };
+            player_start: (0.0, 0.0),
+            entity_spawns: vec![],
+            diagonal_nav: false,
+            flow_target: None,
+            flow_field: HashMap::new(),
+        }
+    }
 
-        // generate large tiles
-        for x in 0..4 {
-            level.tile_big.append(wall.clone(), (x, -1), vec![]);
+    /// Returns the tile graph for a given [`TileSize`]
+    pub fn get_graph(&mut self, size : TileSize) -> &mut TileGraph {
+        match size {
+            TileSize::BIG => {&mut self.tile_big}
+            TileSize::MEDIUM => {&mut self.tile_medium}
+            TileSize::SMALL => {&mut self.tile_small}
         }
+    }
 
-        // generate medium tiles
-        for x in 0..8 {
-            for y in 0..3 {
-                level.tile_medium.append(floor.clone(), (x, y), vec![]);
+    /// Returns the tile graph for a given [`TileSize`], without being able to change it
+    pub fn get_graph_ref(&self, size : TileSize) -> &TileGraph {
+        match size {
+            TileSize::BIG => {&self.tile_big}
+            TileSize::MEDIUM => {&self.tile_medium}
+            TileSize::SMALL => {&self.tile_small}
+        }
+    }
+
+    /// Regenerates `tile_nav` from the tiles in the level. A 16x16 cell is walkable if any tile covers it, and it doesn't
+    /// overlap the collision box of a solid tile. Must be run whenever the level's tiles are changed, so path-finding and
+    /// collision match what is rendered.
+    pub fn build_nav(&mut self) {
+        let nav_size = TileSize::SMALL.get().0 as i32;
+
+        // the cells covered by a tile along with the highest cost of the tiles covering it, and the cells blocked by a collision box
+        let mut covered : HashMap<(i32, i32), u32> = HashMap::new();
+        let mut blocked : HashSet<(i32, i32)> = HashSet::new();
+
+        for graph in [&self.tile_big, &self.tile_medium, &self.tile_small] {
+            let size = graph.tile_size.get().0 as i32;
+            for (coords, tile) in graph.nodes.iter() {
+                // the tile's top left corner in world space
+                let x = coords.0 * size;
+                let y = coords.1 * size;
+
+                for cx in 0..size / nav_size {
+                    for cy in 0..size / nav_size {
+                        let cost = covered.entry((x / nav_size + cx, y / nav_size + cy)).or_insert(1);
+                        *cost = tile.get_cost().max(*cost);
+                    }
+                }
+
+                if let Some(collision_box) = tile.get_collision_box() {
+                    // the collision box is offset by the tile's origin, the same as its texture
+                    let left = x - tile.get_origin().0;
+                    let top = y - tile.get_origin().1;
+                    let right = left + collision_box.0 as i32;
+                    let bottom = top + collision_box.1 as i32;
+
+                    // every cell the box overlaps, touching edges don't count
+                    for cx in left.div_euclid(nav_size)..(right + nav_size - 1).div_euclid(nav_size) {
+                        for cy in top.div_euclid(nav_size)..(bottom + nav_size - 1).div_euclid(nav_size) {
+                            blocked.insert((cx, cy));
+                        }
+                    }
+                }
             }
         }
 
-        // generate navigation tiles
-        for x in 0..16 {
-            for y in 0..6 {
-                level.tile_nav.append(Tile::create_nav(), (x, y), vec![])
+        self.tile_nav = TileGraph::create(TileSize::SMALL);
+        self.tile_nav.set_diagonal(self.diagonal_nav);
+        for (cell, cost) in covered {
+            if !blocked.contains(&cell) {
+                let mut tile = Tile::create_nav();
+                tile.set_cost(cost);
+                self.tile_nav.append(tile, cell, vec![]);
             }
         }
 
         // build graph edges
-        level.tile_nav.build_connections();
+        self.tile_nav.build_connections();
 
-        // return the new level
-        level
+        // the flow field was made for the old graph
+        self.flow_target = None;
+        self.flow_field.clear();
     }
 
+    /// Returns everything solid overlapping an area in world space. This is the collision box of every solid tile, and
+    /// every 16x16 cell that isn't covered by a tile, so entities can't walk off the edge of the level.
+    pub fn get_colliders(&self, area : &Aabb) -> Vec<Aabb> {
+        let mut colliders = vec![];
+
+        for graph in [&self.tile_big, &self.tile_medium, &self.tile_small] {
+            let size = graph.tile_size.get().0 as f32;
+
+            // the tiles the area overlaps, with a tile either side in case a collision box is offset by its origin
+            for x in (area.x / size).floor() as i32 - 1..=(area.right() / size).floor() as i32 + 1 {
+                for y in (area.y / size).floor() as i32 - 1..=(area.bottom() / size).floor() as i32 + 1 {
+                    let tile = match graph.nodes.get(&(x, y)) {
+                        Some(tile) => { tile }
+                        None => { continue }
+                    };
+                    if let Some(collision_box) = tile.get_collision_box() {
+                        let collider = Aabb::new(
+                            x as f32 * size - tile.get_origin().0 as f32,
+                            y as f32 * size - tile.get_origin().1 as f32,
+                            collision_box.0 as f32,
+                            collision_box.1 as f32
+                        );
+                        if collider.intersects(area) {
+                            colliders.push(collider)
+                        }
+                    }
+                }
+            }
+        }
+
+        // the empty space around the level
+        let cell = TileSize::SMALL.get().0 as f32;
+        for x in (area.x / cell).floor() as i32..=(area.right() / cell).floor() as i32 {
+            for y in (area.y / cell).floor() as i32..=(area.bottom() / cell).floor() as i32 {
+                let collider = Aabb::new(x as f32 * cell, y as f32 * cell, cell, cell);
+                if !self.is_covered((x, y)) && collider.intersects(area) {
+                    colliders.push(collider)
+                }
+            }
+        }
+
+        colliders
+    }
 
+    /// Whether a 16x16 cell, in tile space for small tiles, is covered by a tile of any size
+    pub fn is_covered(&self, cell : (i32, i32)) -> bool {
+        let nav_size = TileSize::SMALL.get().0 as i32;
+        [&self.tile_big, &self.tile_medium, &self.tile_small].iter().any(|graph| {
+            // how many cells along one side of a tile in this graph
+            let cells = graph.tile_size.get().0 as i32 / nav_size;
+            graph.nodes.contains_key(&(cell.0.div_euclid(cells), cell.1.div_euclid(cells)))
+        })
+    }
+
+    /// Recomputes the flow field towards the given world space coordinates, usually the player's. This only does anything if
+    /// the coordinates are on a different nav tile to last time, so it is fine to call every frame.
+    pub fn update_flow_field(&mut self, coords : (f32, f32)) {
+        let target = self.tile_nav.to_tile_space(coords);
+        if self.flow_target != Some(target) {
+            self.flow_field = self.tile_nav.distance_map(target);
+            self.flow_target = Some(target);
+        }
+    }
+
+    /// Returns the centre of the next nav tile to move to from the given world space coordinates to follow the flow field. If
+    /// the coordinates are already on the flow field's target tile, the centre of that tile is returned. Returns None if the
+    /// target can't be reached, or there is no flow field.
+    pub fn next_step(&self, coords : (f32, f32)) -> Option<(f32, f32)> {
+        let tile = self.tile_nav.to_tile_space(coords);
+        let size = self.tile_nav.tile_size.get().0 as f32;
+
+        // the centre of a tile in world space
+        let centre = |t : (i32, i32)| ((t.0 as f32 + 0.5) * size, (t.1 as f32 + 0.5) * size);
+
+        if self.flow_target == Some(tile) {
+            return Some(centre(tile))
+        }
+
+        // unreachable tiles aren't in the flow field
+        self.flow_field.get(&tile)?;
+
+        // the neighbour that is cheapest to get to the target through
+        self.tile_nav.connections.get(&tile)?.iter()
+            .filter_map(|(neighbour, cost)| self.flow_field.get(neighbour).map(|d| (d + cost, *neighbour)))
+            .min()
+            .map(|(_, neighbour)| centre(neighbour))
+    }
+
+    /// Loads a level from a JSON file, usually found in `data/<namespace>/levels/`. Tiles are looked up by their
+    /// [`ResourceLocation`] in the given tile map, and the navigation graph is generated from them.
+    pub fn load(path : &str, tiles : &HashMap<String, Tile>) -> Result<Self, String> {
+
+        let json = fs::read_to_string(path).map_err(|e| format!("Could not read level {} : {}", path, e))?;
+
+        if !gjson::valid(json.as_str()) {
+            return Err(format!("Level {} is not valid JSON!", path))
+        }
+
+        let mut level = Self::create_empty();
+
+        // "player" {"x" : float, "y" : float}
+        level.player_start = (
+            gjson::get(json.as_str(), "player.x").f32(),
+            gjson::get(json.as_str(), "player.y").f32()
+        );
+
+        // "diagonal_nav" : bool  - optional, defaults to false
+        level.diagonal_nav = gjson::get(json.as_str(), "diagonal_nav").bool();
+
+        // "tiles" {"big" : [...], "medium" : [...], "small" : [...]}
+        for size in [TileSize::BIG, TileSize::MEDIUM, TileSize::SMALL] {
+            let key = format!("tiles.{}", size.get_name());
+            for placement in gjson::get(json.as_str(), key.as_str()).array() {
+                // {"tile" : string, "x" : int, "y" : int} in tile space
+                let rl = placement.get("tile").to_string();
+                let coords = (placement.get("x").i32(), placement.get("y").i32());
+
+                match tiles.get(&rl) {
+                    Some(tile) => {
+                        level.get_graph(size).append(tile.clone(), coords, vec![])
+                    }
+                    None => {
+                        warn!("Tile {} in level {} could not be found!", rl, path)
+                    }
+                }
+            }
+        }
+
+        // "entities" [{"entity" : string, "x" : float, "y" : float}]
+        for spawn in gjson::get(json.as_str(), "entities").array() {
+            level.entity_spawns.push((
+                ResourceLocation::parse(spawn.get("entity").to_string()),
+                (spawn.get("x").f32(), spawn.get("y").f32())
+            ));
+        }
+
+        // the navigation graph isn't stored in the file
+        level.build_nav();
+
+        Ok(level)
+    }
+
+    /// Saves the level to a JSON file that can be read by [`Level::load`]
+    pub fn save(&self, path : &str) -> Result<(), String> {
+
+        let mut sizes = vec![];
+        for graph in [&self.tile_big, &self.tile_medium, &self.tile_small] {
+            // sort the tiles so saving the same level twice gives the same file
+            let mut coords = graph.nodes.keys().cloned().collect::<Vec<_>>();
+            coords.sort();
+
+            let placements = coords.iter().map(|c| format!(
+                "{{\"tile\":{},\"x\":{},\"y\":{}}}",
+                gjson::tools::escape(graph.nodes.get(c).unwrap().get_resource_location().to_string().as_str()), c.0, c.1
+            )).collect::<Vec<_>>();
+
+            sizes.push(format!("\"{}\":[{}]", graph.tile_size.get_name(), placements.join(",")));
+        }
+
+        let entities = self.entity_spawns.iter().map(|(rl, coords)| format!(
+            "{{\"entity\":{},\"x\":{:?},\"y\":{:?}}}",
+            gjson::tools::escape(rl.to_string().as_str()), coords.0, coords.1
+        )).collect::<Vec<_>>();
+
+        let json = format!(
+            "{{\"player\":{{\"x\":{:?},\"y\":{:?}}},\"diagonal_nav\":{},\"tiles\":{{{}}},\"entities\":[{}]}}",
+            self.player_start.0, self.player_start.1, self.diagonal_nav, sizes.join(","), entities.join(",")
+        );
+
+        // make sure the folder exists before writing to it
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Could not create {} : {}", parent.display(), e))?;
+        }
+
+        fs::write(path, gjson::tools::pretty(json.as_str())).map_err(|e| format!("Could not save level {} : {}", path, e))
+    }
 
 }
 
+/// The width and height of a chunk of tiles in a [`TileGraph`], in tiles
+pub const CHUNK_SIZE : i32 = 16;
+
 /// A graph data structure for holding tiles and their connections
 pub struct TileGraph {
     nodes : HashMap<(i32, i32), Tile>,
     connections : HashMap<(i32,i32), Vec<((i32,i32), u32)>>,
-    tile_size: TileSize
+    // the tile space coordinates of the tiles in each chunk, so only the tiles in an area need to be looked at
+    chunks : HashMap<(i32, i32), Vec<(i32, i32)>>,
+    tile_size: TileSize,
+    diagonal : bool
 }
 
 impl TileGraph {
@@ -112,13 +366,65 @@ impl TileGraph {
         }
         else {
             // using the same key:
-            // add tile to node hashmap
-            self.nodes.insert(value, tile);
+            // add tile to node hashmap, and its chunk if it is a new tile
+            if self.nodes.insert(value, tile).is_none() {
+                self.chunks.entry(Self::chunk_of(value)).or_insert(vec![]).push(value);
+            }
             // add tile to connections hashmap
             self.connections.insert(value, connections);
         }
     }
 
+    /// Remove a tile from the graph given a tile space coordinate, returning the tile if there was one
+    pub fn remove(&mut self, value : (i32, i32)) -> Option<Tile> {
+        self.connections.remove(&value);
+        let removed = self.nodes.remove(&value);
+        if removed.is_some() {
+            let chunk = Self::chunk_of(value);
+            if let Some(tiles) = self.chunks.get_mut(&chunk) {
+                tiles.retain(|t| *t != value);
+                if tiles.is_empty() {
+                    self.chunks.remove(&chunk);
+                }
+            }
+        }
+        removed
+    }
+
+    /// The chunk a tile space coordinate is in
+    fn chunk_of(value : (i32, i32)) -> (i32, i32) {
+        (value.0.div_euclid(CHUNK_SIZE), value.1.div_euclid(CHUNK_SIZE))
+    }
+
+    /// Every tile whose texture overlaps an area of the world, along with its tile space coordinate. Only the chunks
+    /// around the area are looked at, and the tiles are sorted top to bottom then left to right
+    pub fn tiles_in(&self, area : &Aabb) -> Vec<((i32, i32), &Tile)> {
+        let tile_scale = self.tile_size.get().0 as i32;
+        let chunk_scale = (CHUNK_SIZE * tile_scale) as f32;
+
+        // textures can hang over the edge of their tile, so the neighbouring chunks are checked too
+        let left = (area.x / chunk_scale).floor() as i32 - 1;
+        let top = (area.y / chunk_scale).floor() as i32 - 1;
+        let right = (area.right() / chunk_scale).floor() as i32 + 1;
+        let bottom = (area.bottom() / chunk_scale).floor() as i32 + 1;
+
+        let mut tiles = vec![];
+        for cx in left..=right {
+            for cy in top..=bottom {
+                for coords in self.chunks.get(&(cx, cy)).into_iter().flatten() {
+                    let tile = self.nodes.get(coords).unwrap();
+                    let world = ((coords.0 * tile_scale) as f32, (coords.1 * tile_scale) as f32);
+                    if render::texture_bounds(world, &tile.get_asset_data()).intersects(area) {
+                        tiles.push((*coords, tile));
+                    }
+                }
+            }
+        }
+
+        tiles.sort_by_key(|(coords, _)| (coords.1, coords.0));
+        tiles
+    }
+
     /// Add a new tile to the graph given a tile and a world space coordinate
     pub fn append_from_wolrd_space(&mut self, tile : Tile,value : (i32, i32), connections : Vec<((i32, i32), u32)>) {
         self.append(tile, (value.0 / self.tile_size.get().0 as i32, value.1 / self.tile_size.get().0 as i32), connections)
@@ -147,71 +453,221 @@ impl TileGraph {
 
     }
 
-    /// Renders the tile to screen
-    pub fn render(&mut self, player_coords :  (f32, f32), texture : &HashMap<String, Texture>, canvas: &mut WindowCanvas, sf : i32) {
+    /// The area of the world covered by the textures of every tile in the graph, or None if it is empty
+    pub fn get_bounds(&self) -> Option<Aabb> {
+        let tile_scale = self.tile_size.get().0 as i32;
+        self.nodes.iter()
+            .map(|(coords, tile)| render::texture_bounds(((coords.0 * tile_scale) as f32, (coords.1 * tile_scale) as f32), &tile.get_asset_data()))
+            .reduce(|a, b| a.union(&b))
+    }
+
+    /// Renders the tiles that can be seen to screen
+    pub fn render(&mut self, camera : &Camera, target : &mut dyn RenderTarget, sf : i32) {
         // get scale
         let tile_scale = self.tile_size.get().0 as i32;
 
-        // iterate through the tiles
-        for tile in self.nodes.clone() {
+        // iterate through the tiles on screen
+        for tile in self.tiles_in(&camera.visible_area()) {
             // multiply the tile space coordinate by the tile size, to convert to world space
-            tile.1.render(texture, (tile.0.0 * tile_scale, tile.0.1 * tile_scale), canvas, sf, player_coords);
+            tile.1.render((tile.0.0 * tile_scale, tile.0.1 * tile_scale), target, sf, camera);
         }
     }
 
+    /// Get every tile in the graph, keyed by its tile space coordinate
+    pub fn get_nodes(&self) -> &HashMap<(i32, i32), Tile> {
+        &self.nodes
+    }
+
+    /// Get the edges of the graph, keyed by tile space coordinate. Each edge is a neighbouring tile and its cost
+    pub fn get_connections(&self) -> &HashMap<(i32, i32), Vec<((i32, i32), u32)>> {
+        &self.connections
+    }
+
+    /// Get the size of the tiles held by this graph
+    pub fn get_tile_size(&self) -> TileSize {
+        self.tile_size
+    }
+
     /// Create a new TileGraph given a [`TileSize`]
     pub fn create(tile_size: TileSize) -> Self{
         Self {
             nodes: HashMap::new(),
             connections: HashMap::new(),
+            chunks: HashMap::new(),
             tile_size,
+            diagonal : false,
         }
     }
 
     /// Creates all the edges for the graph data structure. Must be run if a tile is added, removed or the graph's tiles are in any way changed. If not, path-finding may not function. Should only need to be run for `tile_nav`.
+    ///
+    /// The cost of an edge is the cost of the tile it leads to, multiplied by [`STRAIGHT_COST`] or [`DIAGONAL_COST`]. Diagonal
+    /// edges are only made if the graph allows them, and both tiles either side of the diagonal are floors, so paths can't cut corners.
     pub fn build_connections(&mut self) {
-        for t in self.nodes.clone() {
+        // checks a tile exists at the given coordinates and is a floor
+        let is_floor = |coords : &(i32, i32)| {
+            self.nodes.get(coords).map(|t| t.clone().get_type() == TileType::FLOOR).unwrap_or(false)
+        };
+
+        let mut all_connections = HashMap::new();
+
+        for t in self.nodes.keys() {
             // North tile
-            let n = (t.0.0, t.0.1 + 1);
+            let n = (t.0, t.1 + 1);
             // East tile
-            let e = (t.0.0 + 1, t.0.1);
+            let e = (t.0 + 1, t.1);
             // South tile
-            let s = (t.0.0, t.0.1 - 1);
+            let s = (t.0, t.1 - 1);
             // West tile
-            let w = (t.0.0 - 1, t.0.1);
+            let w = (t.0 - 1, t.1);
 
             // create connections list
             let mut connections = vec![];
 
             // check a tile exists in the given direction, if so and it is a floor, append it.
-            if self.nodes.get(&n).is_some() {
-                if self.nodes.clone().get_mut(&n).unwrap().get_type() == TileType::FLOOR {
-                    connections.push((n, 1))
+            for d in [n, e, s, w] {
+                if is_floor(&d) {
+                    connections.push((d, STRAIGHT_COST * self.nodes.get(&d).unwrap().get_cost()))
                 }
             }
-            if self.nodes.get(&e).is_some() {
-                if self.nodes.clone().get_mut(&e).unwrap().get_type() == TileType::FLOOR {
-                    connections.push((e, 1))
+
+            if self.diagonal {
+                // each diagonal, along with the two tiles that would be cut through to reach it
+                for (d, a, b) in [
+                    ((t.0 + 1, t.1 + 1), n, e),
+                    ((t.0 + 1, t.1 - 1), s, e),
+                    ((t.0 - 1, t.1 - 1), s, w),
+                    ((t.0 - 1, t.1 + 1), n, w)
+                ] {
+                    if is_floor(&d) && is_floor(&a) && is_floor(&b) {
+                        connections.push((d, DIAGONAL_COST * self.nodes.get(&d).unwrap().get_cost()))
+                    }
                 }
             }
-            if self.nodes.get(&s).is_some() {
-                if self.nodes.clone().get_mut(&s).unwrap().get_type() == TileType::FLOOR {
-                    connections.push((s, 1))
+
+            // add connections to connection hashmap.
+            all_connections.insert(*t, connections);
+        }
+
+        self.connections = all_connections;
+    }
+
+    /// Converts world space coordinates to the tile space coordinates of the tile containing them
+    pub fn to_tile_space(&self, coords : (f32, f32)) -> (i32, i32) {
+        let size = self.tile_size.get().0 as f32;
+        ((coords.0 / size).floor() as i32, (coords.1 / size).floor() as i32)
+    }
+
+    /// Finds the cost of the cheapest path from every tile to the target tile, using Dijkstra's algorithm outwards from the target.
+    /// Tiles that can't reach the target are left out.
+    pub fn distance_map(&self, target : (i32, i32)) -> HashMap<(i32, i32), u32> {
+        let mut distances = HashMap::new();
+
+        if !self.connections.contains_key(&target) {
+            return distances
+        }
+
+        let mut open = BinaryHeap::new();
+        distances.insert(target, 0);
+        open.push(Reverse((0u32, target)));
+
+        while let Some(Reverse((distance, current))) = open.pop() {
+            // an older, longer entry for a tile that has already been visited
+            if distance > *distances.get(&current).unwrap() {
+                continue
+            }
+
+            for (neighbour, _) in self.connections.get(&current).unwrap() {
+                // the cost of moving from the neighbour to this tile, which is the edge going the other way
+                let cost = match self.connections.get(neighbour).and_then(|c| c.iter().find(|(t, _)| *t == current)) {
+                    Some((_, cost)) => { *cost }
+                    None => { continue }
+                };
+
+                let new_distance = distance + cost;
+                if new_distance < *distances.get(neighbour).unwrap_or(&u32::MAX) {
+                    distances.insert(*neighbour, new_distance);
+                    open.push(Reverse((new_distance, *neighbour)));
                 }
             }
-            if self.nodes.get(&w).is_some() {
-                if self.nodes.clone().get_mut(&w).unwrap().get_type() == TileType::FLOOR {
-                    connections.push((w, 1))
+        }
+
+        distances
+    }
+
+    /// Allow diagonal edges when building connections
+    pub fn set_diagonal(&mut self, diagonal : bool) {
+        self.diagonal = diagonal
+    }
+
+    /// Whether diagonal edges are made when building connections
+    pub fn get_diagonal(&self) -> bool {
+        self.diagonal
+    }
+
+    /// An implementation of the A* pathfinding algorithm. Will return a list of world space coordinates from the start tile to
+    /// the target tile, including both, given starting coordinates and target coordinates. Returns None if either coordinate
+    /// isn't on the graph, or the target can't be reached.
+    pub fn path_to(&self, x : i32, y : i32, tx : i32, ty : i32) -> Option<Vec<(i32, i32)>> {
+        let size = self.tile_size.get().0 as i32;
+
+        // start and target in tile space
+        let start = (x.div_euclid(size), y.div_euclid(size));
+        let target = (tx.div_euclid(size), ty.div_euclid(size));
+
+        if !self.connections.contains_key(&start) || !self.connections.contains_key(&target) {
+            return None
+        }
+
+        // distance between each visited node and the start
+        let mut g : HashMap<(i32, i32), u32> = HashMap::new();
+        // previous node/tile for a given node/tile
+        let mut prev_vert : HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+        // nodes to visit, ordered by lowest total cost (distance from start + heuristic) first
+        let mut open = BinaryHeap::new();
+
+        g.insert(start, 0);
+        open.push(Reverse((heuristic(start, target, self.diagonal), start)));
+
+        while let Some(Reverse((_, current))) = open.pop() {
+
+            // reached the target, walk back through the previous nodes to get the path
+            if current == target {
+                let mut path = vec![(current.0 * size, current.1 * size)];
+                let mut vertex = current;
+                while let Some(prev) = prev_vert.get(&vertex) {
+                    vertex = *prev;
+                    path.insert(0, (vertex.0 * size, vertex.1 * size));
                 }
+                return Some(path)
             }
 
-            // add connections to connection hashmap.
-            self.connections.insert(t.0, connections);
+            let current_g = *g.get(&current).unwrap();
+
+            for (neighbour, cost) in self.connections.get(&current).unwrap() {
+                // connections can point at tiles that have since been removed
+                if !self.connections.contains_key(neighbour) {
+                    continue
+                }
+
+                let new_g = current_g + cost;
+                if new_g < *g.get(neighbour).unwrap_or(&u32::MAX) {
+                    g.insert(*neighbour, new_g);
+                    prev_vert.insert(*neighbour, current);
+                    // the old entry for this node stays in the heap, but is visited after this one and does nothing
+                    open.push(Reverse((new_g + heuristic(*neighbour, target, self.diagonal), *neighbour)));
+                }
+            }
         }
+
+        // the target is not connected to the start
+        None
     }
 
-    /// An implementation of the A* pathfinding algorithm. Will return a list of world space coordinates given starting coordinates and target coordinates.
-    pub fn path_to(&mut self, x : i32, y : i32, tx : i32, ty : i32) -> Vec<(i32, i32)> {
+    /// The original implementation of [`TileGraph::path_to`], which checks every node to find the next one to visit. It is
+    /// only kept to benchmark against, and will panic if the start isn't on the graph.
+    #[doc(hidden)]
+    pub fn path_to_linear_scan(&mut self, x : i32, y : i32, tx : i32, ty : i32) -> Vec<(i32, i32)> {
 
         // get a value for infinty
         let inf = u32::MAX;
@@ -280,4 +736,22 @@ impl TileGraph {
 
     }
 
-}
\ No newline at end of file
+}
+
+/// The cost of moving to a neighbouring tile with a cost of 1
+pub const STRAIGHT_COST : u32 = 10;
+/// The cost of moving diagonally to a tile with a cost of 1, roughly 10 * sqrt(2)
+pub const DIAGONAL_COST : u32 = 14;
+
+/// The Manhattan distance between two tile space coordinates, or the octile distance if diagonal moves are allowed. This never
+/// overestimates the cost of a path as every tile costs at least 1
+fn heuristic(a : (i32, i32), b : (i32, i32), diagonal : bool) -> u32 {
+    let dx = a.0.abs_diff(b.0);
+    let dy = a.1.abs_diff(b.1);
+    if diagonal {
+        STRAIGHT_COST * dx.max(dy) + (DIAGONAL_COST - STRAIGHT_COST) * dx.min(dy)
+    }
+    else {
+        STRAIGHT_COST * (dx + dy)
+    }
+}
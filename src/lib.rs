@@ -0,0 +1,35 @@
+//! The simulation, asset registries and level types for the game. The binary in `main.rs` is only a
+//! thin SDL2 front end over this, so tests and tools (level validators, balancing simulators, editors)
+//! can be built against the same code the game ships.
+
+pub mod animation;
+pub mod atlas;
+pub mod camera;
+pub mod entities;
+pub mod entity;
+pub mod entity_definition;
+pub mod entity_list;
+pub mod game;
+pub mod render;
+pub mod render_target;
+pub mod utils;
+pub mod level;
+pub mod tile;
+pub mod resource_location;
+pub mod screen;
+pub mod widgets;
+pub mod screens;
+pub mod widget;
+pub mod sound;
+pub mod loader;
+pub mod headless;
+pub mod viewport;
+pub mod timestep;
+pub mod replay;
+pub mod input;
+pub mod gamepad;
+pub mod collision;
+pub mod command;
+pub mod context;
+pub mod spatial;
+mod tests;
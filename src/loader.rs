@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use log::{info, warn};
+use sdl2::render::TextureCreator;
+use sdl2::video::WindowContext;
+use walkdir::WalkDir;
+use crate::animation::Animation;
+use crate::atlas::{AtlasLayout, TextureAtlas, PAGE_SIZE};
+use crate::entity_definition::EntityDefinition;
+use crate::input::Keybindings;
+use crate::resource_location::ResourceLocation;
+use crate::sound::Sound;
+use crate::tile::{Tile, TileSize, TileType};
+
+// The loaders for every resource type. Textures need an SDL2 texture creator, but sounds, animations, tiles and
+// entities don't, which means they can also be loaded by the headless runner.
+
+/// Walks through an assets directory (usually `./assets/`) and returns every file ending in `extension`
+/// along with its [`ResourceLocation`]. The namespace is the first folder inside the assets directory.
+fn walk_assets(assets : &str, extension : &str) -> Vec<(ResourceLocation, String)> {
+    let mut ret = vec![];
+    let root = assets.trim_end_matches("/");
+
+    for dir in WalkDir::new(format!("{}/", root)) {
+        let path = String::from(dir.unwrap().path().to_str().unwrap()).replace("\\", "/");
+        if path.clone().to_lowercase().ends_with(extension) {
+            // everything after the assets directory, >>namespace<</path/to/file.ext
+            let relative = path.split_at(root.len() + 1).1.trim_start_matches("/");
+
+            // name space is the first element
+            let namespace = relative.split("/").collect::<Vec<_>>()[0];
+
+            // the path is just everything after the namespace
+            let file = relative.split_at(namespace.len() + 1).1;
+
+            ret.push((ResourceLocation::new(namespace, file), format!("{}/{}/{}", root, namespace, file)));
+        }
+    }
+
+    ret
+}
+
+/// Loads every PNG in the assets directory and packs them into an [`AtlasLayout`], without needing a GPU
+pub fn pack_textures(assets : &str) -> AtlasLayout {
+    // counter to count how many objects are loaded for the debug logs
+    let mut counter = 0;
+
+    info!("Loading textures...");
+    let mut images = vec![];
+
+    for (rl, path) in walk_assets(assets, ".png") {
+        //load the image
+        match image::open(path.as_str()) {
+            Ok(image) => {
+                images.push((rl.clone(), image.to_rgba8()));
+                info!("Loaded texture : {}", rl.to_string());
+                counter+=1;
+            }
+            Err(e) => { warn!("Texture {} could not be loaded! {}", rl.to_string(), e) }
+        }
+    }
+
+    let layout = AtlasLayout::pack(images, PAGE_SIZE);
+    info!("{} textures loaded onto {} atlas pages!", counter, layout.pages.len());
+
+    layout
+}
+
+/// Loads every PNG in the assets directory into a [`TextureAtlas`]
+pub fn load_textures<'a>(assets : &str, texture_creator : &'a TextureCreator<WindowContext>) -> TextureAtlas<'a> {
+    TextureAtlas::create(pack_textures(assets), texture_creator).unwrap()
+}
+
+/// Loads every OGG file in the assets directory as a [`Sound`]
+pub fn load_sounds(assets : &str) -> HashMap<String, Sound> {
+    let mut counter = 0;
+
+    info!("Loading sounds...");
+    // create hashmap
+    let mut sounds : HashMap<String, Sound> = HashMap::new();
+
+    for (rl, path) in walk_assets(assets, ".ogg") {
+        //load the sound
+        let sound = Sound {
+            path,
+            resource_location: rl.clone(),
+        };
+
+        // insert the hashmap
+        sounds.insert(rl.to_string(), sound);
+
+        info!("Loaded sound : {}", rl.to_string());
+        counter+=1;
+    }
+    info!("{} sounds loaded!", counter);
+
+    sounds
+}
+
+/// Loads every `.anim.json` file in the assets directory as an [`Animation`]. Invalid animations are skipped.
+pub fn load_animations(assets : &str) -> HashMap<String, Animation> {
+    let mut counter = 0;
+
+    info!("Loading animations...");
+    let mut animations : HashMap<String, Animation> = HashMap::new();
+
+    for (rl, path) in walk_assets(assets, ".anim.json") {
+        let json = fs::read_to_string(path).unwrap();
+
+        match Animation::parse(rl.clone(), json.as_str()) {
+            Ok(animation) => {
+                animations.insert(rl.to_string(), animation);
+                info!("Loaded animation : {}", rl.to_string());
+                counter+=1;
+            }
+            Err(e) => { warn!("{}", e) }
+        }
+    }
+    info!("{} animations loaded!", counter);
+
+    animations
+}
+
+/// Loads every tile from `<data>/<namespace>/tiles/`. The tile system is entirely data driven.
+pub fn load_tiles(data : &str) -> HashMap<String, Tile> {
+    let mut counter = 0;
+    let root = data.trim_end_matches("/");
+
+    // initialise tiles
+    info!("Loading tiles...");
+
+    //create hashmap
+    let mut tiles: HashMap<String, Tile> = HashMap::new();
+    // get the immediate subdirectories for the name spaces
+    let namespaces = fs::read_dir(root).unwrap();
+
+    // iterate through the namespaces
+    for namepath in namespaces {
+
+        // get the actual namespace
+        let namespace = namepath.unwrap().file_name().to_str().unwrap().to_string();
+
+        if !namespace.clone().contains(".") {
+
+            for dir in WalkDir::new(format!("{}/{}/tiles/", root, namespace.clone())) {
+                let path = String::from(dir.unwrap().path().to_str().unwrap()).replace("\\", "/");
+                // if the file is tile data, continue
+                if path.clone().to_lowercase().ends_with(".json") {
+                    // get the json file as a string
+                    let json = fs::read_to_string(path.clone()).unwrap();
+
+                    // read the values from the json file
+
+                    // "name" : string
+                    let name = gjson::get(json.as_str(), "name");
+
+                    // the ResourceLocation of this JSON file
+                    let resource_location = ResourceLocation::new(
+                        &*namespace.clone(),
+                        path.split(format!("/{}/", namespace).as_str()).collect::<Vec<_>>()[1]);
+
+                    // "resource_location" : string
+                    let texture = ResourceLocation::parse(
+                        gjson::get(json.as_str(), "resource_location")
+                        .to_string());
+
+                    // "uv" {"x" : int,  "y" : int}
+                    let uv : (u32, u32) = (
+                        gjson::get(json.as_str(), "uv.x").to_string().parse::<u32>().unwrap(),
+                        gjson::get(json.as_str(), "uv.y").to_string().parse::<u32>().unwrap()
+                    );
+
+                    // "type" : string    - might remove this bit as it may not be needed
+                    let ttype = TileType::parse(gjson::get(json.as_str(), "type").to_string());
+
+                    // "size" : string
+                    let size = TileSize::parse(gjson::get(json.as_str(), "size").to_string().as_str());
+
+                    // "origin" {"x" : int ,  "y" :  int }  - at the moment it refers to where the centre point of the sprite is,
+                    //                                  but will be changed to be the centre point of the hitbox, as the sprites
+                    //                                  should all have the origin of (0,0) to render correctly
+                    let origin : (i32, i32) = (
+                        gjson::get(json.as_str(), "origin.x").to_string().parse::<i32>().unwrap(),
+                        gjson::get(json.as_str(), "origin.y").to_string().parse::<i32>().unwrap()
+                    );
+
+                    // "collision" : bool
+                    let collision : bool = gjson::get(json.as_str(), "collision").to_string().parse::<bool>().unwrap();
+                    let collison_box : Option<(u32,u32)>;
+
+                    // "collision_box" {"x" : int ,  "y" :  int }
+                    if collision {
+                        collison_box = Some((
+                            gjson::get(json.as_str(), "collision_box.x").to_string().parse::<u32>().unwrap(),
+                            gjson::get(json.as_str(), "collision_box.y").to_string().parse::<u32>().unwrap()
+                        ));
+                    }
+                    else {
+                        collison_box = None
+                    }
+
+                    // "cost" : int  - optional, how expensive the tile is to path-find over, defaults to 1
+                    let cost = gjson::get(json.as_str(), "cost");
+                    let cost = if cost.exists() { cost.u32() } else { 1 };
+
+                    // create the tile and append it to the hashmap
+                    let tile = Tile::create(name.to_string(), resource_location.clone(), texture, uv, ttype, size, origin, collision, collison_box, cost);
+                    tiles.insert(resource_location.to_string(), tile);
+
+                    info!("Loaded tile : {}", resource_location.to_string());
+
+                    counter += 1;
+                }
+
+            }
+        }
+    }
+
+    info!("{} tiles loaded!", counter);
+
+    tiles
+}
+
+/// Loads every entity definition from `<data>/<namespace>/entities/`. Each definition is registered as `<namespace>:entity/<path>`,
+/// without the `.json`, so `data/game/entities/turret.json` is `game:entity/turret`. Invalid definitions are skipped.
+pub fn load_entities(data : &str) -> HashMap<String, EntityDefinition> {
+    let mut counter = 0;
+    let root = data.trim_end_matches("/");
+
+    info!("Loading entities...");
+
+    let mut entities : HashMap<String, EntityDefinition> = HashMap::new();
+
+    for namepath in fs::read_dir(root).unwrap() {
+
+        let namespace = namepath.unwrap().file_name().to_str().unwrap().to_string();
+
+        if !namespace.contains(".") {
+
+            // not every namespace has to add entities
+            let folder = format!("{}/{}/entities/", root, namespace);
+            for dir in WalkDir::new(folder.clone()).into_iter().filter_map(|dir| dir.ok()) {
+                let path = String::from(dir.path().to_str().unwrap()).replace("\\", "/");
+                if path.to_lowercase().ends_with(".json") {
+                    let json = fs::read_to_string(path.clone()).unwrap();
+
+                    // the path inside the entities folder, without the extension
+                    let name = path.split_at(folder.len()).1.trim_start_matches("/");
+                    let name = &name[..name.len() - ".json".len()];
+                    let resource_location = ResourceLocation::new(&namespace, format!("entity/{}", name).as_str());
+
+                    match EntityDefinition::parse(resource_location.clone(), json.as_str()) {
+                        Ok(definition) => {
+                            entities.insert(resource_location.to_string(), definition);
+                            info!("Loaded entity : {}", resource_location.to_string());
+                            counter += 1;
+                        }
+                        Err(e) => { warn!("{}", e) }
+                    }
+                }
+            }
+        }
+    }
+
+    info!("{} entities loaded!", counter);
+
+    entities
+}
+
+/// Loads the player's keybindings from a file written by [`Keybindings::save`], or the default keys if there isn't one yet
+pub fn load_keybindings(path : &str) -> Keybindings {
+    if !Path::new(path).exists() {
+        info!("No keybindings at {}, using the default keys", path);
+        return Keybindings::default()
+    }
+
+    match Keybindings::load(path) {
+        Ok(keybindings) => {
+            info!("Loaded keybindings from {}", path);
+            keybindings
+        }
+        Err(e) => {
+            warn!("{}", e);
+            Keybindings::default()
+        }
+    }
+}
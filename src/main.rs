@@ -1,52 +1,19 @@
-mod entities;
-mod entity;
-mod game;
-mod render;
-mod utils;
-mod level;
-mod tile;
-mod resource_location;
-mod screen;
-mod widgets;
-mod screens;
-mod widget;
-mod sound;
-mod tests;
-
-use crate::sound::Sound;
-use std::collections::HashMap;
-use std::{env, fs};
-use std::fs::File;
-use std::hash::Hash;
-use std::io::BufReader;
-use std::ops::DerefMut;
-use std::path::Path;
-use crate::entities::{enemy, player};
-use crate::screens::main_menu_screen;
-use crate::entity::Entity;
-use crate::game::Game;
-use num::clamp;
-use render::TARGET_DIMENSIONS;
-use sdl2::event::Event;
-use sdl2::image::LoadTexture;
-use sdl2::keyboard::Keycode;
-use sdl2::rect::Rect;
+use std::env;
 use std::time::Instant;
-use gjson::Value;
-use log::info;
-use sdl2::event::Event::KeyDown;
-use sdl2::render::Texture;
-use walkdir::WalkDir;
-use resource_location::ResourceLocation;
-use widget::Widget;
-use crate::level::Level;
-use crate::render::AssetData;
-use crate::screen::Screen;
-use crate::tile::{Tile, TileSize, TileType};
-use crate::widget::Alignment;
-use crate::widgets::source_widget;
-use crate::widgets::source_widget::SourceWidget;
-use crate::screens::main_menu_screen::MainMenuScreen;
+use log::{info, warn};
+use sdl2::event::Event;
+use nea_project::game::Game;
+use nea_project::render_target::CanvasTarget;
+use nea_project::replay::{Recording, TickInput};
+use nea_project::screen::Screen;
+use nea_project::screens::hud_screen::HudScreen;
+use nea_project::screens::main_menu_screen::MainMenuScreen;
+use nea_project::timestep::FixedTimestep;
+use nea_project::viewport::Viewport;
+use nea_project::{headless, loader, render, timestep, utils};
+
+/// Where the player's keybindings are kept
+const KEYBINDINGS_PATH : &str = "./settings/keybindings.json";
 
 fn main() {
 
@@ -55,31 +22,53 @@ fn main() {
     // start logger
     utils::init_logger();
 
+    // run without a window if asked to, e.g. `--headless 30` runs the demo level for 30 simulated seconds
+    let args : Vec<String> = env::args().collect();
+    if let Some(i) = args.iter().position(|a| a == "--headless") {
+        let seconds = args.get(i + 1).and_then(|s| s.parse::<f32>().ok()).unwrap_or(10.0);
+        info!("Running headless for {} seconds", seconds);
+        let mut runner = headless::HeadlessRunner::create("./data/", "./assets/", headless::DEFAULT_DELTA);
+        runner.load_demo_level();
+        runner.run_for(seconds);
+        runner.report();
+        return;
+    }
+
     info!("Initialising SDL2");
 
     // start SDL2
     let sdl_ctx = sdl2::init().unwrap();
-    let scale_factor =
-        sdl_ctx.video().unwrap().current_display_mode(0).unwrap().w / TARGET_DIMENSIONS.0 as i32;
     let video_subsys = sdl_ctx.video().unwrap();
-    // the actual dimensions of the game screen, as they won't always be 320/180 due to differing aspect ratios
-    let dims = ((sdl_ctx.video().unwrap().current_display_mode(0).unwrap().w / scale_factor) as u32, (sdl_ctx.video().unwrap().current_display_mode(0).unwrap().h / scale_factor) as u32);
 
-    render::get_actual_dimensions().lock().unwrap().set(dims);
-
-    let scale_offset = (sdl_ctx.video().unwrap().current_display_mode(0).unwrap().h / scale_factor as i32) - 180;
-    //todo : get the difference in height and display 2 black bars to give 16:9 ratio screen
+    // run in a window at a chosen scale if asked to, e.g. `--windowed 3` for a 960x540 window. Otherwise the game fills
+    // the screen
+    let windowed = args.iter().position(|a| a == "--windowed")
+        .map(|i| args.get(i + 1).and_then(|s| s.parse::<i32>().ok()).unwrap_or(3));
 
     //hide mouse
     sdl_ctx.mouse().show_cursor(false);
 
     // create window
-    let mut window = video_subsys
-        .window("[Game name here]", TARGET_DIMENSIONS.0, TARGET_DIMENSIONS.1)
-        .vulkan()
-        .fullscreen_desktop()
-        .build()
-        .unwrap();
+    let mut window = match windowed {
+        Some(sf) => {
+            let viewport = Viewport::windowed(sf);
+            video_subsys
+                .window("[Game name here]", viewport.window.0, viewport.window.1)
+                .vulkan()
+                .position_centered()
+                .build()
+                .unwrap()
+        }
+        None => {
+            let display = video_subsys.current_display_mode(0).unwrap();
+            video_subsys
+                .window("[Game name here]", display.w as u32, display.h as u32)
+                .vulkan()
+                .fullscreen_desktop()
+                .build()
+                .unwrap()
+        }
+    };
 
     info!("complete");
 
@@ -87,181 +76,23 @@ fn main() {
     &window.set_mouse_grab(true);
 
     //create canvas
-    let mut canvas = &mut window.into_canvas().build().unwrap();
+    let mut canvas = window.into_canvas().build().unwrap();
     let texture_creator = canvas.texture_creator();
 
-    // counter to count how many objects are loaded for the debug logs
-    let mut counter = 0;
-
-    // initialise textures - new
-    info!("Loading textures...");
-    // create hashmap
-    let mut textures : HashMap<String, Texture> = HashMap::new();
-    // iterate through the assets directory
-    for dir in WalkDir::new("./assets/") {
-        let mut path = String::from(dir.unwrap().path().to_str().unwrap()).replace("\\", "/");
-        // if the file is an image, save it - in future there will likely be a hashmap for other files, like animation data or other bits idk yet
-        if path.clone().to_lowercase().ends_with(".png") {
-            // create the resource location
-            let mut rl = ResourceLocation::empty();
-
-            // split the path by \s
-            let split : Vec<_> = path.split("/").collect();
-
-            // name space is in ./assets/>>namespace<<, so it is the third element in the list
-            let namespace = &split[2];
-            rl.set_namespace(namespace.to_string());
-
-            // the path is just everything after the namespace
-            let path = path.split(format!("/{}/", namespace).as_str()).collect::<Vec<_>>()[1];
-            rl.set_path(path.to_string());
-
-            //load the texture
-            let texture = texture_creator.load_texture(format!("./assets/{}/{}", namespace, path).as_str());
-
-            // insert the hashmap
-            textures.insert(rl.clone().to_string(), texture.unwrap());
-
-            info!("Loaded texture : {}", rl.to_string());
-            counter+=1;
-        }
-    }
-    info!("{} textures loaded!", counter);
-
-    counter = 0;
-
-    info!("Loading sounds...");
-    // create hashmap
-    let mut sounds : HashMap<String, Sound> = HashMap::new();
-
-    // iterate through the assets directory
-    for dir in WalkDir::new("./assets/") {
-        let mut path = String::from(dir.unwrap().path().to_str().unwrap()).replace("\\", "/");
-        // if the file is a sound, save it
-        if path.clone().to_lowercase().ends_with(".ogg") {
-            // create the resource location
-            let mut rl = ResourceLocation::empty();
-
-            // split the path by \s
-            let split : Vec<_> = path.split("/").collect();
-
-            // name space is in ./assets/>>namespace<<, so it is the third element in the list
-            let namespace = &split[2];
-            rl.set_namespace(namespace.to_string());
-
-            // the path is just everything after the namespace
-            let path = path.split(format!("/{}/", namespace).as_str()).collect::<Vec<_>>()[1];
-            rl.set_path(path.to_string());
-
-            //load the sound
-            let sound = Sound {
-                path : format!("assets/{}/{}", namespace, path),
-                resource_location: rl.clone(),
-            };
-
-            // insert the hashmap
-            sounds.insert(rl.clone().to_string(), sound);
-
-
-            info!("Loaded sound : {}", rl.to_string());
-            counter+=1;
-        }
-    }
-    info!("{} sounds loaded!", counter);
-
-    counter = 0;
-    // entirely data driven tile system
-
-    // initialise tiles
-    info!("Loading tiles...");
-
-    //create hashmap
-    let mut tiles: HashMap<String, Tile> = HashMap::new();
-    // get the immediate subdirectories for the name spaces
-    let namespaces = fs::read_dir("./data").unwrap();
-
-    // iterate through the namespaces
-    for namepath in namespaces {
-
-        // get the actual namespace
-        let mut namespace = String::from(namepath.unwrap().path().to_str().unwrap()).replace("\\", "/");
-        //                                            .\data\>>namespace<<
-        namespace = namespace.split("/").collect::<Vec<_>>()[2].to_string();
-
-        if !namespace.clone().contains(".") {
-
-            for dir in WalkDir::new(format!("./data/{}/tiles/", namespace.clone())) {
-                let path = String::from(dir.unwrap().path().to_str().unwrap()).replace("\\", "/");
-                // if the file is tile data, continue
-                if path.clone().to_lowercase().ends_with(".json") {
-                    // get the json file as a string
-                    let json = fs::read_to_string(path.clone()).unwrap();
-
-                    // read the values from the json file
-
-                    // "name" : string
-                    let name = gjson::get(json.as_str(), "name");
-
-                    // the ResourceLocation of this JSON file
-                    let resource_location = ResourceLocation::new(
-                        &*namespace.clone(),
-                        path.split(format!("/{}/", namespace).as_str()).collect::<Vec<_>>()[1]);
-
-                    // "resource_location" : string
-                    let texture = ResourceLocation::parse(
-                        gjson::get(json.as_str(), "resource_location")
-                        .to_string());
-
-                    // "uv" {"x" : int,  "y" : int}
-                    let uv : (u32, u32) = (
-                        gjson::get(json.as_str(), "uv.x").to_string().parse::<u32>().unwrap(),
-                        gjson::get(json.as_str(), "uv.y").to_string().parse::<u32>().unwrap()
-                    );
-
-                    // "type" : string    - might remove this bit as it may not be needed
-                    let ttype = TileType::parse(gjson::get(json.as_str(), "type").to_string());
-
-                    // "size" : string
-                    let size = TileSize::parse(gjson::get(json.as_str(), "size").to_string().as_str());
-
-                    // "origin" {"x" : int ,  "y" :  int }  - at the moment it refers to where the centre point of the sprite is,
-                    //                                  but will be changed to be the centre point of the hitbox, as the sprites
-                    //                                  should all have the origin of (0,0) to render correctly
-                    let origin : (i32, i32) = (
-                        gjson::get(json.as_str(), "origin.x").to_string().parse::<i32>().unwrap(),
-                        gjson::get(json.as_str(), "origin.y").to_string().parse::<i32>().unwrap()
-                    );
-
-                    // "collision" : bool
-                    let collision : bool = gjson::get(json.as_str(), "collision").to_string().parse::<bool>().unwrap();
-                    let mut collison_box : Option<(u32,u32)>;
-
-                    // "collision_box" {"x" : int ,  "y" :  int }
-                    if collision {
-                        collison_box = Some((
-                            gjson::get(json.as_str(), "collision_box.x").to_string().parse::<u32>().unwrap(),
-                            gjson::get(json.as_str(), "collision_box.y").to_string().parse::<u32>().unwrap()
-                        ));
-                    }
-                    else {
-                        collison_box = None
-                    }
-
-                    // create the tile and append it to the hashmap
-                    let tile = Tile::create(name.to_string(), resource_location.clone(), texture, uv, ttype, size, origin, collision, collison_box);
-                    tiles.insert(resource_location.to_string(), tile);
-
-                    info!("Loaded tile : {}", resource_location.to_string());
-
-                    counter += 1;
-                }
-
-            }
-        }
-    }
-
-    info!("{} tiles loaded!", counter);
+    // the game is scaled up as much as it can be by a whole number, with black bars filling the rest of the window. The
+    // game screen is always the same size, so every monitor sees the same amount of the level
+    let viewport = Viewport::fit(canvas.output_size().unwrap());
+    let scale_factor = viewport.sf;
+    let dims = viewport.get_dimensions();
+    render::get_actual_dimensions().lock().unwrap().set(dims);
+    info!("Drawing at {}x scale into {:?}", scale_factor, viewport.rect);
 
+    // load textures, sounds, animations and tiles
+    let textures = loader::load_textures("./assets/", &texture_creator);
+    let sounds = loader::load_sounds("./assets/");
+    let animations = loader::load_animations("./assets/");
+    let tiles = loader::load_tiles("./data/");
+    let entity_definitions = loader::load_entities("./data/");
 
     // register event pump to handle inputs
     let mut event_pump = sdl_ctx.event_pump().unwrap();
@@ -269,6 +100,11 @@ fn main() {
     canvas.present();
 
     info!("Initiating game controller");
+    let controller_subsystem = sdl_ctx.game_controller().unwrap();
+
+    // controllers only send events whilst they are open. SDL2 says every controller already plugged in has been added
+    // when the game starts, so they are all opened as they are added
+    let mut controllers = vec![];
 
     let mut game = Game::initiate();
 
@@ -278,58 +114,146 @@ fn main() {
 
     // append hashmaps to game instance
     game.tiles = tiles;
+    game.entity_definitions = entity_definitions;
     game.sounds = sounds;
+    game.animations = animations;
     game.dims = dims;
 
-    /// Delta refers to the time taken between showing two frames. This value is often used for physics related operations, as this allows the simulation to not be affected by the frame rate of the computer.
-    /// You can see that happening in [`Entity::physics`]
-    // initiate delta
-    let mut delta: f32 = 0.0;
+    // the player's own keys, changed on the settings screen
+    game.keybindings = loader::load_keybindings(KEYBINDINGS_PATH);
+    game.keybindings_path = Some(KEYBINDINGS_PATH.to_string());
+
+    // Delta refers to the time each tick of the game lasts. This value is often used for physics related operations, see
+    // Entity::physics. The game is ticked in fixed steps so that it behaves the same no matter the frame rate, with as
+    // many ticks run each frame as the time it took needs
+    let mut timestep = FixedTimestep::new(timestep::STEP);
+    let mut last_frame = Instant::now();
+
+    // events that haven't been given to a tick yet
+    let mut pending_events = vec![];
+
+    // play back the input of a recorded run instead of reading it, e.g. `--replay recordings/bug.replay.json`
+    let mut replay = match args.iter().position(|a| a == "--replay").and_then(|i| args.get(i + 1)) {
+        Some(path) => {
+            match Recording::load(path) {
+                Ok(recording) => {
+                    info!("Replaying {} ticks from {}", recording.ticks.len(), path);
+                    // the run has to start in the same place, at the same speed
+                    timestep.step = recording.delta;
+                    if let Some(level) = &recording.level {
+                        if let Err(e) = game.load_level(level) {
+                            warn!("{}", e)
+                        }
+                        game.current_screen = Some(HudScreen::create(&mut game));
+                    }
+                    Some(recording)
+                }
+                Err(e) => {
+                    warn!("{}", e);
+                    None
+                }
+            }
+        }
+        None => { None }
+    };
+
+    // save the input of every tick when the game closes, e.g. `--record recordings/bug.replay.json`
+    let record_path = args.iter().position(|a| a == "--record").and_then(|i| args.get(i + 1)).cloned();
+    let mut recording = record_path.as_ref().map(|_| Recording::new(timestep.step, None));
+    let mut tick = 0;
 
     // load the test level
     //game.current_level = Some(Level::create_test_level(&tiles));
 
     info!("Game instance initiated!");
 
-    while game.running {
-        canvas.clear();
+    // everything is drawn to the window through the render target
+    let mut target = CanvasTarget::create(canvas, textures, viewport);
 
-        // begin timer for delta
-        let start = Instant::now();
+    while game.running {
+        // clears the black bars too
+        target.canvas.clear();
 
         // draw background texture
-        canvas
-            .copy_ex(
-                &textures.get("game:background.png").unwrap(),
-                None,
-                Rect::new(0, 0, dims.0, dims.1),
-                0.0,
-                None,
-                false,
-                false,
-            )
-            .expect("TODO: panic message");
+        render::draw_background(&mut target);
 
         // get keys that are held down
-        game.held_keys = vec![];
-        for key in event_pump.keyboard_state().pressed_scancodes() {
-            game.held_keys.push(key);
-        }
+        let held_keys = event_pump.keyboard_state().pressed_scancodes().collect::<Vec<_>>();
 
         // get keys that are pressed
-        game.events = vec![];
         for event in event_pump.poll_iter() {
-            game.events.push(event.clone());
+            match event {
+                Event::ControllerDeviceAdded { which, .. } => {
+                    match controller_subsystem.open(which) {
+                        Ok(controller) => {
+                            info!("Connected controller {}", controller.name());
+                            controllers.push(controller)
+                        }
+                        Err(e) => { warn!("Could not open controller {} : {}", which, e) }
+                    }
+                }
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    controllers.retain(|c| c.instance_id() != which)
+                }
+                _ => {}
+            }
+            pending_events.push(event.clone());
         }
 
-        // run a game cycle
-        game.cycle(delta, (event_pump.mouse_state().x() / scale_factor) as u32, (event_pump.mouse_state().y() / scale_factor) as u32, dims);
+        // the mouse position on the game screen
+        let mut mouse = viewport.to_game((event_pump.mouse_state().x(), event_pump.mouse_state().y()));
+
+        // run as many game cycles as the last frame took. Key presses are only given to the first, so they only happen once
+        let now = Instant::now();
+        let steps = timestep.advance((now - last_frame).as_secs_f32());
+        last_frame = now;
+        for _ in 0..steps {
+            let mut input = TickInput {
+                held_keys : held_keys.clone(),
+                events : std::mem::take(&mut pending_events),
+                mouse
+            };
 
-        // run game render
-        game.render(canvas, scale_factor, &textures, dims, (event_pump.mouse_state().x() / scale_factor) as u32, (event_pump.mouse_state().y() / scale_factor) as u32);
+            // whilst replaying, only closing the window is listened to
+            if let Some(recording) = &replay {
+                if input.events.iter().any(|e| matches!(e, Event::Quit { .. })) {
+                    game.running = false;
+                    break
+                }
+                match recording.get(tick) {
+                    Some(recorded) => { input = recorded.clone() }
+                    None => {
+                        info!("Replay finished after {} ticks", tick);
+                        replay = None;
+                    }
+                }
+            }
+
+            input.apply(&mut game);
+            game.cycle(timestep.step, input.mouse.0, input.mouse.1, dims);
+            mouse = input.mouse;
+            if let Some(recording) = recording.as_mut() {
+                recording.push(input);
+            }
+            tick += 1;
+
+            if !game.running {
+                break
+            }
+        }
+
+        // run game render, part of the way to the next tick
+        game.interpolation = timestep.alpha();
+        game.render(&mut target, scale_factor, dims, mouse.0, mouse.1);
 
         // present screen buffer to user
-        canvas.present();
-        delta = start.elapsed().as_secs_f32();
+        target.canvas.present();
+    }
+
+    if let (Some(recording), Some(path)) = (recording, record_path) {
+        match recording.save(&path) {
+            Ok(_) => { info!("Saved {} ticks of input to {}", recording.ticks.len(), path) }
+            Err(e) => { warn!("{}", e) }
+        }
     }
 }
@@ -1,6 +1,5 @@
 use once_cell::sync::OnceCell;
 use sdl2::rect::Rect;
-use sdl2::render::{Texture, WindowCanvas};
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::iter::Copied;
@@ -9,6 +8,8 @@ use log::warn;
 use sdl2::keyboard::Scancode::I;
 use sdl2::libc::stat;
 use sdl2::pixels::Color;
+use crate::collision::Aabb;
+use crate::render_target::RenderTarget;
 use crate::render;
 use crate::resource_location::ResourceLocation;
 
@@ -16,9 +17,9 @@ pub const TARGET_DIMENSIONS: (u32, u32) = (320, 180);
 
 /// A struct which holds all relevant information for displaying an item's texture.
 pub struct AssetData {
-    pub(crate) uv: Option<Rect>,
-    pub(crate) origin: (i32, i32),
-    pub (crate) resource_location: ResourceLocation,
+    pub uv: Option<Rect>,
+    pub origin: (i32, i32),
+    pub resource_location: ResourceLocation,
 }
 
 impl AssetData {
@@ -123,39 +124,87 @@ pub fn get_icons() -> &'static Mutex<HashMap<&'static str, AssetData>> {
 
 
 
-/// Draws textures to the screen pixel-perfectly
-pub fn draw_pp_texture(x: i32, y: i32, ass: &AssetData, mut canvas: &mut WindowCanvas, sf: i32, textures : &HashMap<String, Texture>) {
+/// The area of the world that can be seen, given the world space coordinates at the centre of the screen. Anything outside
+/// of it doesn't need to be drawn
+pub fn visible_area(centre: (f32, f32)) -> Aabb {
+    let dims = get_actual_dimensions().lock().unwrap().get();
+    // an extra pixel on each side, as screen coordinates are rounded
+    Aabb::new(centre.0 - (dims.0 / 2) as f32 - 1.0, centre.1 - (dims.1 / 2) as f32 - 1.0, dims.0 as f32 + 2.0, dims.1 as f32 + 2.0)
+}
+
+/// The area of the world a texture covers when it is drawn at some world space coordinates
+pub fn texture_bounds(coords: (f32, f32), ass: &AssetData) -> Aabb {
+    let uv = ass.uv.unwrap_or(Rect::new(0, 0, 0, 0));
+    Aabb::new(coords.0 - ass.origin.0 as f32, coords.1 - ass.origin.1 as f32, uv.width() as f32, uv.height() as f32)
+}
+
+/// Draws textures to a [`RenderTarget`] pixel-perfectly
+pub fn draw_pp_texture(x: i32, y: i32, ass: &AssetData, target: &mut dyn RenderTarget, sf: i32) {
     let uv = ass.uv.unwrap();
-    let tex_rect = Rect::new(x - ass.origin.0, y - ass.origin.1, uv.w as u32, uv.h as u32);
     let mut id = ass.resource_location.clone();
 
-    canvas
-        .set_scale(sf as f32, sf as f32)
-        .expect("TODO: panic message");
+    target.set_scale(sf);
 
-    // get texture from the map
-    let mut texture = textures.get(&id.to_string());
+    // get texture from the atlas
+    let mut region = target.get_region(&id.to_string());
 
     // if the texture is missing, show missing texture
-    if texture.is_none(){
+    if region.is_none(){
         if !get_missing_list().lock().unwrap().contains(&&id.to_string()) {
             warn!("Texture at {} could not be found!", id.to_string())
         }
         get_missing_list().lock().unwrap().push(id.clone().to_string());
-        texture = textures.get(&ResourceLocation::new("game", "missing.png").to_string());
+        region = target.get_region(&ResourceLocation::new("game", "missing.png").to_string());
+    }
+
+
+    let region = region.unwrap();
+
+    // find the UV on the atlas page, anything outside of the original texture isn't drawn
+    let atlas_uv = match region.remap(Some(uv)) {
+        Some(atlas_uv) => { atlas_uv }
+        None => { return }
+    };
+    let clipped = (atlas_uv.x() - region.rect.x() - uv.x(), atlas_uv.y() - region.rect.y() - uv.y());
+    let tex_rect = Rect::new(x - ass.origin.0 + clipped.0, y - ass.origin.1 + clipped.1, atlas_uv.width(), atlas_uv.height());
+
+    target.copy(region.page, atlas_uv, tex_rect);
+}
+
+/// The width and height of each character in `gui/font.png`, including the gap after it
+pub const FONT_SIZE: (u32, u32) = (6, 8);
+
+/// Draws a line of text with the pixel font in `gui/font.png`, with the top left of the first character at the given
+/// screen coordinates. Lowercase letters are drawn as uppercase, and characters the font doesn't have are left blank
+pub fn draw_text(x: i32, y: i32, text: &str, target: &mut dyn RenderTarget, sf: i32) {
+    for (i, c) in text.to_uppercase().chars().enumerate() {
+        // the font is laid out in ASCII order, 16 characters a row, starting from a space
+        let index = c as u32;
+        if !(33..128).contains(&index) {
+            continue
+        }
+        let index = index - 32;
+        let ass = AssetData {
+            uv: Some(Rect::new(((index % 16) * FONT_SIZE.0) as i32, ((index / 16) * FONT_SIZE.1) as i32, FONT_SIZE.0, FONT_SIZE.1)),
+            origin: (0, 0),
+            resource_location: ResourceLocation::new("game", "gui/font.png"),
+        };
+        draw_pp_texture(x + (i as u32 * FONT_SIZE.0) as i32, y, &ass, target, sf);
     }
+}
 
+/// How wide a line of text is when drawn by [`draw_text`]
+pub fn text_width(text: &str) -> u32 {
+    text.chars().count() as u32 * FONT_SIZE.0
+}
 
-    canvas
-        .copy_ex(&texture.unwrap(),
-                 uv,
-                 tex_rect,
-                 0.0,
-                 None,
-                 false,
-                 false,
-        )
-        .expect("TODO: panic message");
+/// Stretches the background texture over the whole of a [`RenderTarget`]
+pub fn draw_background(target: &mut dyn RenderTarget) {
+    let dims = target.get_dimensions();
+    if let Some(region) = target.get_region("game:background.png") {
+        target.set_scale(1);
+        target.copy(region.page, region.rect, Rect::new(0, 0, dims.0, dims.1));
+    }
 }
 
 
@@ -0,0 +1,169 @@
+use image::{Rgba, RgbaImage};
+use log::warn;
+use sdl2::rect::Rect;
+use sdl2::render::WindowCanvas;
+use crate::atlas::{AtlasLayout, AtlasRegion, TextureAtlas};
+use crate::viewport::Viewport;
+
+/// Something textures can be drawn onto, either the window or an image in memory. Every texture is drawn through
+/// [`draw_pp_texture`], so a target only has to know where its textures are and how to copy part of one.
+///
+/// [`draw_pp_texture`]: crate::render::draw_pp_texture
+pub trait RenderTarget {
+
+    /// Where a texture is in the atlas, given the resource location of the original file as a string
+    fn get_region(&self, rl : &str) -> Option<AtlasRegion>;
+
+    /// Set how much everything drawn is scaled up by
+    fn set_scale(&mut self, sf : i32);
+
+    /// Set the area of the target everything is drawn into. Nothing is drawn outside of it
+    fn set_viewport(&mut self, viewport : Viewport);
+
+    /// Copy an area of an atlas page to an area of the viewport, before it is scaled. The area is stretched if the sizes
+    /// don't match
+    fn copy(&mut self, page : usize, src : Rect, dst : Rect);
+
+    /// The width and height of the viewport, in actual pixels
+    fn get_dimensions(&self) -> (u32, u32);
+}
+
+/// Where an area of the viewport ends up on the target once it is scaled
+fn to_target(viewport : &Viewport, sf : i32, dst : Rect) -> Rect {
+    Rect::new(
+        viewport.rect.x() + dst.x() * sf,
+        viewport.rect.y() + dst.y() * sf,
+        dst.width() * sf as u32,
+        dst.height() * sf as u32
+    )
+}
+
+/// Draws to the game window with SDL2, using a [`TextureAtlas`] on the GPU
+pub struct CanvasTarget<'a> {
+    pub canvas : WindowCanvas,
+    pub textures : TextureAtlas<'a>,
+    viewport : Viewport,
+    sf : i32
+}
+
+impl<'a> CanvasTarget<'a> {
+
+    /// Create a target drawing into an area of the window
+    pub fn create(canvas : WindowCanvas, textures : TextureAtlas<'a>, viewport : Viewport) -> Self {
+        let mut ret = Self {
+            canvas,
+            textures,
+            viewport,
+            sf : 1
+        };
+        ret.set_viewport(viewport);
+        ret
+    }
+}
+
+impl<'a> RenderTarget for CanvasTarget<'a> {
+    fn get_region(&self, rl : &str) -> Option<AtlasRegion> {
+        self.textures.get(rl).map(|(_, region)| region)
+    }
+
+    fn set_scale(&mut self, sf : i32) {
+        self.sf = sf
+    }
+
+    fn set_viewport(&mut self, viewport : Viewport) {
+        self.viewport = viewport;
+        // keeps sprites half off the edge of the screen from being drawn over the black bars
+        self.canvas.set_clip_rect(Some(viewport.rect));
+    }
+
+    fn copy(&mut self, page : usize, src : Rect, dst : Rect) {
+        let texture = self.textures.get_page(page);
+        // scaled here rather than by SDL2, so the viewport doesn't have to line up with the scale
+        let dst = to_target(&self.viewport, self.sf.max(1), dst);
+        // a failed copy only loses this texture for a frame, so carry on drawing the rest
+        if let Err(e) = self.canvas.copy_ex(texture, src, dst, 0.0, None, false, false) {
+            warn!("Texture could not be drawn from atlas page {}! {}", page, e)
+        }
+    }
+
+    fn get_dimensions(&self) -> (u32, u32) {
+        self.viewport.rect.size()
+    }
+}
+
+/// Draws into an image in memory without a window or GPU, blending textures the same way SDL2 does. Used to check what a
+/// frame looks like in tests.
+pub struct SoftwareTarget<'a> {
+    pub image : RgbaImage,
+    layout : &'a AtlasLayout,
+    viewport : Viewport,
+    sf : i32
+}
+
+impl<'a> SoftwareTarget<'a> {
+
+    /// Create a transparent target of the given size, drawing textures from an atlas layout. The viewport covers the
+    /// whole image until it is set
+    pub fn create(dims : (u32, u32), layout : &'a AtlasLayout) -> Self {
+        Self {
+            image : RgbaImage::new(dims.0, dims.1),
+            layout,
+            viewport : Viewport {
+                window : dims,
+                sf : 1,
+                rect : Rect::new(0, 0, dims.0, dims.1)
+            },
+            sf : 1
+        }
+    }
+}
+
+impl<'a> RenderTarget for SoftwareTarget<'a> {
+    fn get_region(&self, rl : &str) -> Option<AtlasRegion> {
+        self.layout.regions.get(rl).copied()
+    }
+
+    fn set_scale(&mut self, sf : i32) {
+        self.sf = sf
+    }
+
+    fn set_viewport(&mut self, viewport : Viewport) {
+        self.viewport = viewport
+    }
+
+    fn copy(&mut self, page : usize, src : Rect, dst : Rect) {
+        let page = &self.layout.pages[page];
+        let dst = to_target(&self.viewport, self.sf.max(1), dst);
+        let (w, h) = self.image.dimensions();
+        let clip = self.viewport.rect;
+
+        // the area of the image being drawn to, kept inside the viewport and the image
+        let left = dst.left().max(clip.left()).max(0);
+        let top = dst.top().max(clip.top()).max(0);
+        let right = dst.right().min(clip.right()).min(w as i32);
+        let bottom = dst.bottom().min(clip.bottom()).min(h as i32);
+
+        for y in top..bottom {
+            for x in left..right {
+                // nearest neighbour, the same as SDL2's default scaling
+                let sx = src.x() + ((x - dst.x()) as i64 * src.width() as i64 / dst.width() as i64) as i32;
+                let sy = src.y() + ((y - dst.y()) as i64 * src.height() as i64 / dst.height() as i64) as i32;
+                let pixel = page.get_pixel(sx as u32, sy as u32);
+                blend(self.image.get_pixel_mut(x as u32, y as u32), pixel);
+            }
+        }
+    }
+
+    fn get_dimensions(&self) -> (u32, u32) {
+        self.viewport.rect.size()
+    }
+}
+
+/// Draw one pixel over another, using SDL2's blend mode
+fn blend(dst : &mut Rgba<u8>, src : &Rgba<u8>) {
+    let alpha = src[3] as u32;
+    for i in 0..3 {
+        dst[i] = ((src[i] as u32 * alpha + dst[i] as u32 * (255 - alpha)) / 255) as u8;
+    }
+    dst[3] = (alpha + dst[3] as u32 * (255 - alpha) / 255) as u8;
+}
@@ -0,0 +1,262 @@
+use std::fs;
+use std::path::Path;
+use gjson::Value;
+use sdl2::event::Event;
+use sdl2::keyboard::{Mod, Scancode};
+use sdl2::mouse::{MouseButton, MouseWheelDirection};
+use crate::game::Game;
+use crate::{gamepad, input};
+
+/// Everything given to the game during one tick, see [`Game::cycle`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TickInput {
+    pub held_keys : Vec<Scancode>,
+    pub events : Vec<Event>,
+    /// The mouse position on the game screen
+    pub mouse : (u32, u32)
+}
+
+impl TickInput {
+
+    /// Give the held keys and events to the game, ready for its next cycle
+    pub fn apply(&self, game : &mut Game) {
+        game.held_keys = self.held_keys.clone();
+        game.events = self.events.clone();
+    }
+}
+
+/// The input for every tick of a run, which reproduces the run exactly when fed back into a game that starts in the same
+/// place with the same delta. Only the events the game reacts to are kept, see [`Recording::save`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recording {
+    /// How long each tick lasts
+    pub delta : f32,
+    /// The level the run started in, or None if it started on the main menu
+    pub level : Option<String>,
+    pub ticks : Vec<TickInput>
+}
+
+impl Recording {
+
+    /// Create an empty recording
+    pub fn new(delta : f32, level : Option<String>) -> Self {
+        Self {
+            delta,
+            level,
+            ticks : vec![]
+        }
+    }
+
+    /// Add the input for the next tick
+    pub fn push(&mut self, input : TickInput) {
+        self.ticks.push(input)
+    }
+
+    /// The input for a tick, or None if the recording has ended
+    pub fn get(&self, tick : usize) -> Option<&TickInput> {
+        self.ticks.get(tick)
+    }
+
+    /// Loads a recording from a JSON file written by [`Recording::save`]
+    pub fn load(path : &str) -> Result<Self, String> {
+
+        let json = fs::read_to_string(path).map_err(|e| format!("Could not read recording {} : {}", path, e))?;
+
+        if !gjson::valid(json.as_str()) {
+            return Err(format!("Recording {} is not valid JSON!", path))
+        }
+
+        // "delta" : float, "level" : string - optional
+        let level = gjson::get(json.as_str(), "level");
+        let mut recording = Self::new(
+            gjson::get(json.as_str(), "delta").f32(),
+            if level.exists() { Some(level.to_string()) } else { None }
+        );
+
+        // "ticks" [{"held" : [int], "mouse" : [int, int], "events" : [...]}]
+        for tick in gjson::get(json.as_str(), "ticks").array() {
+            let held_keys = tick.get("held").array().iter().filter_map(|k| input::scancode_from_i32(k.i32())).collect();
+            let mouse = (tick.get("mouse.0").u32(), tick.get("mouse.1").u32());
+            let events = tick.get("events").array().iter().filter_map(parse_event).collect();
+
+            recording.push(TickInput {
+                held_keys,
+                events,
+                mouse
+            })
+        }
+
+        Ok(recording)
+    }
+
+    /// Saves the recording to a JSON file that can be read by [`Recording::load`]. Events the game doesn't react to, such as
+    /// the window moving, are left out
+    pub fn save(&self, path : &str) -> Result<(), String> {
+
+        let ticks = self.ticks.iter().map(|tick| {
+            let held = tick.held_keys.iter().map(|k| (*k as i32).to_string()).collect::<Vec<_>>();
+            let events = tick.events.iter().filter_map(write_event).collect::<Vec<_>>();
+            format!(
+                "{{\"held\":[{}],\"mouse\":[{},{}],\"events\":[{}]}}",
+                held.join(","), tick.mouse.0, tick.mouse.1, events.join(",")
+            )
+        }).collect::<Vec<_>>();
+
+        let level = match &self.level {
+            Some(level) => { format!("\"level\":{},", gjson::tools::escape(level.as_str())) }
+            None => { String::new() }
+        };
+
+        // one tick a line, as a long run has a lot of them
+        let json = format!("{{\"delta\":{:?},{}\"ticks\":[\n{}\n]}}\n", self.delta, level, ticks.join(",\n"));
+
+        // make sure the folder exists before writing to it
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Could not create {} : {}", parent.display(), e))?;
+        }
+
+        fs::write(path, json).map_err(|e| format!("Could not save recording {} : {}", path, e))
+    }
+}
+
+/// Write an event as JSON, if it is one the game reacts to. Timestamps and window IDs aren't kept, and neither are
+/// keycodes, as keys are only matched by their scancode, see [`Keybindings`]
+///
+/// [`Keybindings`]: crate::input::Keybindings
+fn write_event(event : &Event) -> Option<String> {
+    match event {
+        Event::Quit { .. } => {
+            Some("{\"type\":\"quit\"}".to_string())
+        }
+        Event::KeyDown { scancode, keymod, repeat, .. } => {
+            Some(write_key("key_down", scancode, keymod, *repeat))
+        }
+        Event::KeyUp { scancode, keymod, repeat, .. } => {
+            Some(write_key("key_up", scancode, keymod, *repeat))
+        }
+        Event::MouseButtonDown { mouse_btn, clicks, x, y, .. } => {
+            Some(format!("{{\"type\":\"mouse_down\",\"button\":{},\"clicks\":{},\"x\":{},\"y\":{}}}", *mouse_btn as u8, clicks, x, y))
+        }
+        Event::MouseButtonUp { mouse_btn, clicks, x, y, .. } => {
+            Some(format!("{{\"type\":\"mouse_up\",\"button\":{},\"clicks\":{},\"x\":{},\"y\":{}}}", *mouse_btn as u8, clicks, x, y))
+        }
+        Event::MouseWheel { x, y, direction, .. } => {
+            Some(format!("{{\"type\":\"mouse_wheel\",\"x\":{},\"y\":{},\"direction\":{}}}", x, y, direction.to_ll()))
+        }
+        // controllers are stored by number too, and which controller it was isn't kept as they all control the player
+        Event::ControllerAxisMotion { axis, value, .. } => {
+            Some(format!("{{\"type\":\"controller_axis\",\"axis\":{},\"value\":{}}}", *axis as i32, value))
+        }
+        Event::ControllerButtonDown { button, .. } => {
+            Some(format!("{{\"type\":\"controller_down\",\"button\":{}}}", *button as i32))
+        }
+        Event::ControllerButtonUp { button, .. } => {
+            Some(format!("{{\"type\":\"controller_up\",\"button\":{}}}", *button as i32))
+        }
+        Event::ControllerDeviceRemoved { .. } => {
+            Some("{\"type\":\"controller_removed\"}".to_string())
+        }
+        _ => { None }
+    }
+}
+
+fn write_key(kind : &str, scancode : &Option<Scancode>, keymod : &Mod, repeat : bool) -> String {
+    // keys are stored by number, -1 being no key
+    format!(
+        "{{\"type\":\"{}\",\"scancode\":{},\"keymod\":{},\"repeat\":{}}}",
+        kind,
+        scancode.map(|s| s as i32).unwrap_or(-1),
+        keymod.bits(),
+        repeat
+    )
+}
+
+/// Read an event written by [`write_event`]
+fn parse_event(json : &Value) -> Option<Event> {
+    match json.get("type").str() {
+        "quit" => {
+            Some(Event::Quit { timestamp : 0 })
+        }
+        "key_down" => {
+            Some(Event::KeyDown {
+                timestamp : 0,
+                window_id : 0,
+                keycode : None,
+                scancode : input::scancode_from_i32(json.get("scancode").i32()),
+                keymod : Mod::from_bits_truncate(json.get("keymod").u32() as u16),
+                repeat : json.get("repeat").bool()
+            })
+        }
+        "key_up" => {
+            Some(Event::KeyUp {
+                timestamp : 0,
+                window_id : 0,
+                keycode : None,
+                scancode : input::scancode_from_i32(json.get("scancode").i32()),
+                keymod : Mod::from_bits_truncate(json.get("keymod").u32() as u16),
+                repeat : json.get("repeat").bool()
+            })
+        }
+        "mouse_down" => {
+            Some(Event::MouseButtonDown {
+                timestamp : 0,
+                window_id : 0,
+                which : 0,
+                mouse_btn : MouseButton::from_ll(json.get("button").u32() as u8),
+                clicks : json.get("clicks").u32() as u8,
+                x : json.get("x").i32(),
+                y : json.get("y").i32()
+            })
+        }
+        "mouse_up" => {
+            Some(Event::MouseButtonUp {
+                timestamp : 0,
+                window_id : 0,
+                which : 0,
+                mouse_btn : MouseButton::from_ll(json.get("button").u32() as u8),
+                clicks : json.get("clicks").u32() as u8,
+                x : json.get("x").i32(),
+                y : json.get("y").i32()
+            })
+        }
+        "mouse_wheel" => {
+            let (x, y) = (json.get("x").i32(), json.get("y").i32());
+            Some(Event::MouseWheel {
+                timestamp : 0,
+                window_id : 0,
+                which : 0,
+                x,
+                y,
+                direction : MouseWheelDirection::from_ll(json.get("direction").u32()),
+                precise_x : x as f32,
+                precise_y : y as f32
+            })
+        }
+        "controller_axis" => {
+            Some(Event::ControllerAxisMotion {
+                timestamp : 0,
+                which : 0,
+                axis : gamepad::axis_from_i32(json.get("axis").i32())?,
+                value : json.get("value").i32() as i16
+            })
+        }
+        "controller_down" => {
+            Some(Event::ControllerButtonDown {
+                timestamp : 0,
+                which : 0,
+                button : gamepad::button_from_i32(json.get("button").i32())?
+            })
+        }
+        "controller_up" => {
+            Some(Event::ControllerButtonUp {
+                timestamp : 0,
+                which : 0,
+                button : gamepad::button_from_i32(json.get("button").i32())?
+            })
+        }
+        "controller_removed" => {
+            Some(Event::ControllerDeviceRemoved { timestamp : 0, which : 0 })
+        }
+        _ => { None }
+    }
+}
@@ -1,6 +1,6 @@
-use std::collections::HashMap;
 use sdl2::event::Event;
-use sdl2::render::{Texture, WindowCanvas};
+use crate::render_target::RenderTarget;
+use crate::context::Context;
 use crate::game::Game;
 use crate::widget::Widget;
 
@@ -11,6 +11,11 @@ pub trait Screen {
     /// Get the contained widgets
     fn get_widgets(&mut self) -> &mut Vec<Vec<Box<dyn Widget>>>;
 
+    #[must_use]
+    /// Get the widget chosen with the keyboard or a controller, by column and row as given to [`Screen::add_widget`]. None
+    /// whilst the mouse is being used instead
+    fn get_focus(&mut self) -> &mut Option<(usize, usize)>;
+
     /// Add a widget to the screen
     fn add_widget(&mut self, widget : Box<dyn Widget>, x : usize, y : usize) {
         let mut listy = self.get_widgets().get_mut(y);
@@ -20,31 +25,25 @@ pub trait Screen {
         self.get_widgets().get_mut(y).unwrap().insert(x, widget);
     }
 
-    /// Get the game instance holding the screen
-    #[must_use]
-    fn get_game(&mut self) -> *mut Game;
-
-    /// Set the game instance holding the screen
-    #[must_use]
-    fn set_game(&mut self, game : *mut Game);
-
-
     #[must_use]
     /// Create a new instance of a screen
     fn create(game : &mut Game) -> Box<Self> where Self: Sized;
 
     /// What the screen does every frame
-    fn cycle(&mut self, mousex : u32, mousey : u32, events: Vec<Event>) {
+    fn cycle(&mut self, mousex : u32, mousey : u32, ctx : &mut Context) {
 
-        self.tick(mousex, mousey, events);
+        self.tick(mousex, mousey, ctx);
 
-        // get mutable game instance
-        let game = unsafe { &mut *self.get_game() };
+        // let the widgets update anything they display from the game
+        for widgets in self.get_widgets() {
+            for w in widgets {
+                w.tick(ctx);
+            }
+        }
 
         // for every widget contained within the screen
         for widgets in self.get_widgets() {
-            // widgets is a 2d list, this is because I had intended to use this for controller support,
-            // which was ultimately scrapped for the time being.
+            // widgets is a 2d list, rows of columns, so a controller can move between them, see Screen::navigate
             for w in widgets {
                 let _ = w.set_selected(false); // default to unselected
                 let mut coords = w.correct_coords(); // correct widget coords (Jank but works)
@@ -57,24 +56,99 @@ pub trait Screen {
                 if (coords.0 <= mousex as i32 && coords.0 as u32 + uv.unwrap().width() > mousex) && (coords.1 <= mousey as i32 && coords.1 as u32 + uv.unwrap().height() > mousey) {
                     // set widget to selected
                     let _ = w.set_selected(true);
-                    game.use_finger = true; // use finger mouse pointer for increased visual indication of button
                     break
                 }
-                else {
-                    // else do nothing
-                    game.use_finger= false;
+            }
+        }
+    }
+
+    /// Whether the widgets can be focused with the keyboard or a controller. Screens shown whilst playing or editing a
+    /// level turn this off, as the same keys and buttons are used there for other things
+    fn uses_focus(&mut self) -> bool {
+        true
+    }
+
+    /// The widget a step away from another in the widget grid, by column and row as given to [`Screen::add_widget`], for
+    /// moving between widgets with the keyboard or a controller. Empty rows are skipped, and the column is kept within the row moved to.
+    /// Moving from nothing, or from a widget that isn't there, goes to the first widget
+    fn navigate(&mut self, from : Option<(usize, usize)>, step : (i32, i32)) -> Option<(usize, usize)> {
+        let widgets = self.get_widgets();
+        let first = widgets.iter().position(|row| !row.is_empty()).map(|y| (0, y));
+
+        let (x, y) = match from {
+            Some((x, y)) if widgets.get(y).is_some_and(|row| x < row.len()) => { (x, y) }
+            _ => { return first }
+        };
+
+        // find the next row in the direction moved that has something in it, staying put if there isn't one
+        let mut row = y as i32;
+        if step.1 != 0 {
+            row += step.1.signum();
+            while row >= 0 && (row as usize) < widgets.len() && widgets[row as usize].is_empty() {
+                row += step.1.signum();
+            }
+            if row < 0 || row as usize >= widgets.len() {
+                row = y as i32;
+            }
+        }
+
+        let len = widgets[row as usize].len() as i32;
+        let column = (x as i32 + step.0).clamp(0, len - 1);
+        Some((column as usize, row as usize))
+    }
+
+    /// The widget after another, reading each row from left to right and going back to the first after the last. Moving
+    /// from nothing goes to the first widget
+    fn next(&mut self, from : Option<(usize, usize)>) -> Option<(usize, usize)> {
+        let order = self.get_widgets().iter().enumerate()
+            .flat_map(|(y, row)| (0..row.len()).map(move |x| (x, y)))
+            .collect::<Vec<_>>();
+        match from.and_then(|f| order.iter().position(|w| *w == f)) {
+            Some(i) => { order.get((i + 1) % order.len()).copied() }
+            None => { order.first().copied() }
+        }
+    }
+
+    /// Move the focus a step through the widget grid, see [`Screen::navigate`]
+    fn move_focus(&mut self, step : (i32, i32)) {
+        let from = *self.get_focus();
+        let focus = self.navigate(from, step);
+        *self.get_focus() = focus;
+    }
+
+    /// Move the focus on to the next widget, see [`Screen::next`]
+    fn focus_next(&mut self) {
+        let from = *self.get_focus();
+        let focus = self.next(from);
+        *self.get_focus() = focus;
+    }
+
+    /// Select the focused widget in place of the one under the mouse, so it is drawn as selected and pressed by confirm.
+    /// Does nothing whilst there is no focus
+    fn show_focus(&mut self) {
+        if let Some(focus) = *self.get_focus() {
+            for (y, widgets) in self.get_widgets().iter_mut().enumerate() {
+                for (x, w) in widgets.iter_mut().enumerate() {
+                    let _ = w.set_selected((x, y) == focus);
                 }
             }
         }
     }
 
-    fn tick(&mut self, mousex : u32, mousey : u32, events: Vec<Event>) {}
+    /// Whether the screen is waiting for a key press itself, such as to rebind a key. Whilst it is, the game doesn't act on
+    /// any key presses
+    fn captures_keys(&mut self) -> bool {
+        false
+    }
+
+    /// Implement this function to add extra functionality to a screen. Changes to the game are made through the [`Context`]
+    fn tick(&mut self, mousex : u32, mousey : u32, ctx : &mut Context) {}
 
     /// Render the screen to the ... Screen - the actual real one the player sees
-    fn render(&mut self, textures : &HashMap<String, Texture>, sf : i32, canvas : &mut WindowCanvas, dims : (u32, u32), debug : bool) {
+    fn render(&mut self, sf : i32, target : &mut dyn RenderTarget, dims : (u32, u32), debug : bool) {
         for widgets in self.get_widgets() {
             for w in widgets {
-                w.render(textures, sf, canvas, debug);
+                w.render(sf, target, debug);
             }
         }
     }
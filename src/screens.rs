@@ -1,7 +1,8 @@
-pub(crate) mod main_menu_screen;
-pub(crate) mod room_editor_screen;
-pub(crate) mod hud_screen;
-pub(crate) mod you_died;
+pub mod main_menu_screen;
+pub mod room_editor_screen;
+pub mod hud_screen;
+pub mod you_died;
+pub mod settings_screen;
 
 use crate::screen::Screen;
 
@@ -11,8 +11,8 @@ use crate::widgets::score_widget::ScoreWidget;
 use crate::widgets::source_widget::SourceWidget;
 
 pub struct HudScreen {
-    game : *mut Game,
     widgets : Vec<Vec<Box<dyn Widget>>>,
+    focus : Option<(usize, usize)>,
 }
 
 impl Screen for HudScreen {
@@ -20,12 +20,12 @@ impl Screen for HudScreen {
         &mut self.widgets
     }
 
-    fn get_game(&mut self) -> *mut Game {
-        self.game
+    fn get_focus(&mut self) -> &mut Option<(usize, usize)> {
+        &mut self.focus
     }
 
-    fn set_game(&mut self, game: *mut Game) {
-        self.game = game;
+    fn uses_focus(&mut self) -> bool {
+        false
     }
 
     fn create(game: &mut Game) -> Box<Self>
@@ -34,10 +34,10 @@ impl Screen for HudScreen {
     {
         let mut ret = Self{
             widgets: vec![],
-            game,
+            focus: None,
         };
-        ret.add_widget(PlayerHealthWidget::create(Alignment::TOP, (game.dims.0/2) as i32, 0, game), 0, 0);
-        ret.add_widget(ScoreWidget::create(Alignment::TOP, 0, 0, game), 0, 0);
+        ret.add_widget(PlayerHealthWidget::create(Alignment::TOP, (game.dims.0/2) as i32, 0), 0, 0);
+        ret.add_widget(ScoreWidget::create(Alignment::TOP, 0, 0), 0, 0);
         Box::new(ret)
     }
 
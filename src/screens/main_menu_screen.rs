@@ -1,4 +1,6 @@
 use sdl2::event::Event;
+use crate::command::Command;
+use crate::context::Context;
 use crate::game::{DyslexiaMode, Game};
 use crate::screen::Screen;
 use crate::widget::{Alignment, Widget};
@@ -7,10 +9,12 @@ use crate::widgets::enum_widget::{EnumWidget, WidgetEnum};
 use crate::widgets::play_widget::PlayWidget;
 use crate::widgets::quit_widget::QuitWidget;
 use crate::widgets::source_widget::SourceWidget;
+use crate::widgets::text_button_widget::TextButtonWidget;
+use crate::screens::settings_screen::SettingsScreen;
 
 pub struct MainMenuScreen{
     widgets : Vec<Vec<Box<dyn Widget>>>,
-    game : *mut Game,
+    focus : Option<(usize, usize)>,
 
 }
 
@@ -19,34 +23,33 @@ impl Screen for MainMenuScreen {
         &mut self.widgets
     }
 
-    fn get_game(&mut self) -> *mut Game {
-        self.game
+    fn get_focus(&mut self) -> &mut Option<(usize, usize)> {
+        &mut self.focus
     }
 
-    fn set_game(&mut self, game: *mut Game) {
-        self.game = game;
-    }
-
-
     fn create(game: &mut Game) -> Box<Self>
     where
         Self: Sized
     {
         let mut ret = Self {
             widgets: vec![],
-            game,
+            focus: None,
         };
-        ret.add_widget(SourceWidget::create(Alignment::LEFT, 20, 0, game), 0, 0);
-        ret.add_widget(PlayWidget::create(Alignment::LEFT, 60, 0, game), 0, 0);
-        ret.add_widget(QuitWidget::create(Alignment::LEFT, 60, -40, game), 0, 0);
-        ret.add_widget(EditorWidget::create(Alignment::LEFT, 20, 30, game), 0, 0);
-        ret.add_widget(EnumWidget::create(Alignment::LEFT, 20, -60, game, DyslexiaMode::OFF, 18, 32), 0, 0);
+        // laid out in the grid as they are on screen, the small buttons on the left and the big ones on the right
+        ret.add_widget(EditorWidget::create(Alignment::LEFT, 20, 30), 0, 0);
+        ret.add_widget(PlayWidget::create(Alignment::LEFT, 60, 0), 1, 0);
+        ret.add_widget(SourceWidget::create(Alignment::LEFT, 20, 0), 0, 1);
+        ret.add_widget(QuitWidget::create(Alignment::LEFT, 60, -40), 1, 1);
+        ret.add_widget(EnumWidget::create(Alignment::LEFT, 20, -60, DyslexiaMode::OFF, 18, 32), 0, 2);
+        ret.add_widget(TextButtonWidget::create("Settings", Alignment::RIGHT, -60, -70, |ctx| {
+            ctx.push(Command::ChangeScreen(|game| SettingsScreen::create(game)))
+        }), 1, 2);
         Box::new(ret)
     }
 
-    fn tick(&mut self, mousex: u32, mousey: u32, events: Vec<Event>) {
+    fn tick(&mut self, mousex: u32, mousey: u32, ctx: &mut Context) {
 
-        let mut dyslexia = unsafe { &mut *self.game }.dyslexia_mode.clone();
+        let mut dyslexia = ctx.dyslexia_mode.clone();
 
         for widgets in self.get_widgets() {
             for w in widgets {
@@ -56,7 +59,7 @@ impl Screen for MainMenuScreen {
             }
         }
 
-        unsafe { &mut *self.game }.dyslexia_mode = dyslexia;
+        ctx.push(Command::SetDyslexiaMode(dyslexia));
 
     }
 }
\ No newline at end of file
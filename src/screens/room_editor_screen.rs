@@ -1,15 +1,18 @@
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Mutex;
+use log::{info, warn};
 use sdl2::event::Event;
-use sdl2::keyboard::Keycode;
-use sdl2::keyboard::Keycode::D;
 use sdl2::mouse::MouseButton;
 use sdl2::rect::Rect;
-use sdl2::render::{Texture, WindowCanvas};
+use uuid::Uuid;
+use crate::command::Command;
+use crate::context::Context;
 use crate::entities::dummy::DummyEntity;
 use crate::entity::Entity;
 use crate::game::Game;
-use crate::level::{Level, TileGraph};
+use crate::input::Action;
+use crate::level::Level;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
 use crate::screen::Screen;
@@ -17,100 +20,225 @@ use crate::tile::{Tile, TileSize};
 use crate::widget::{Alignment, Widget};
 use crate::widgets::enum_widget::{EnumWidget, WidgetEnum};
 
+/// Where the editor saves the level it is building, and loads it from when opened
+pub const EDITOR_LEVEL_PATH : &str = "./data/game/levels/editor.json";
+
+/// A single change made to the level, kept so it can be undone and redone
+struct Edit {
+    size : TileSize,
+    coords : (i32, i32),
+    before : Option<Tile>,
+    after : Option<Tile>
+}
+
+/// A screen for building levels. Controls:
+/// - Left click to place the selected tile, right click to erase
+/// - B or the mouse wheel to cycle through the tiles of the selected size
+/// - Ctrl+Z to undo, Ctrl+Y to redo
+/// - Ctrl+S to save to [`EDITOR_LEVEL_PATH`], or the path given to [`RoomEditorScreen::create_at`], Ctrl+L to reload it
 pub struct RoomEditorScreen {
     sf : i32,
+    // where the level is saved and loaded from
+    level_path : String,
     widgets : Vec<Vec<Box<dyn Widget>>>,
-    game : *mut Game,
-    //editor_level : Level,
+    focus : Option<(usize, usize)>,
     selected_scale : TileSize,
     centre : (f32, f32),
-    highlight_index : usize,
+    highlight : Uuid,
     tiles : HashMap<String, Tile>,
-
+    // the resource locations of every tile of the selected size
+    palette : Vec<String>,
+    palette_index : usize,
+    undo_stack : Vec<Edit>,
+    redo_stack : Vec<Edit>,
+    // tiles set this tick, which the level won't have until the game applies the commands for them
+    pending : Vec<(TileSize, (i32, i32), Option<Tile>)>
 }
 
 impl RoomEditorScreen {
 
-    pub fn add_test_tiles(&mut self, tiles : &HashMap<String, Tile>) {
-        let tile = Some(tiles.get("game:tiles/wall.json").unwrap().clone());
-        let tile3 = Some(tiles.get("game:tiles/dirt.json").unwrap().clone());
-        let tile4 = Some(tiles.get("game:tiles/orange.json").unwrap().clone());
-    }
-
-}
-
-impl Screen for RoomEditorScreen {
-    fn get_widgets(&mut self) -> &mut Vec<Vec<Box<dyn Widget>>> {
-        &mut self.widgets
-    }
-
-
-    fn get_game(&mut self) -> *mut Game {
-        self.game
-    }
-
-    fn set_game(&mut self, game: *mut Game) {
-        self.game = game
-    }
-
-    fn create(game: &mut Game) -> Box<Self>
-    where
-        Self: Sized
-    {
+    /// Create an editor that saves to and loads from a level file other than [`EDITOR_LEVEL_PATH`], carrying on from the
+    /// level there if it exists
+    pub fn create_at(game : &mut Game, level_path : &str) -> Box<Self> {
 
         //game.draw_mouse = false;
 
-        let mut dummy_player = DummyEntity::create(game, AssetData::empty());
-        let highlight_object = DummyEntity::create(game,
+        let highlight_object = DummyEntity::create(
                                                    AssetData {
                                                        uv: Some(Rect::new(0,0,16, 16)),
                                                        origin: (0, 0),
                                                        resource_location: ResourceLocation::new("game", "tiles/highlight.png"),
                                                    }
         );
-        dummy_player.set_coords((0.0, 0.0));
-        game.entities.push(Box::new(Mutex::new(dummy_player)));
-        game.player = Some(game.entities.len() -1 );
-        let highlight_index = game.entities.len();
-        game.entities.push(Box::new(Mutex::new(highlight_object)));
-
-        let tile  = game.tiles.get("game:tiles/wall.json");
-
-        let mut level = Level {
-            tile_big: TileGraph::create(TileSize::BIG),
-            tile_medium: TileGraph::create(TileSize::MEDIUM),
-            tile_small: TileGraph::create(TileSize::SMALL),
-            tile_nav: TileGraph::create(TileSize::SMALL),
-        };
+        // the editor looks at the origin, and can look outside of the level
+        game.camera.snap_to((0.0, 0.0));
+        game.camera.set_bounds(None);
+        let highlight = game.entities.insert(Box::new(Mutex::new(highlight_object)));
 
         let mut ret = Self{
             sf : game.sf,
+            level_path : level_path.to_string(),
             widgets: vec![],
-            game,
-            //editor_level : level,
+            focus: None,
             selected_scale : TileSize::SMALL,
             centre : (0.0, 0.0),
-            highlight_index,
-            tiles : game.tiles.clone()
+            highlight,
+            tiles : game.tiles.clone(),
+            palette : vec![],
+            palette_index : 0,
+            undo_stack : vec![],
+            redo_stack : vec![],
+            pending : vec![]
         };
 
-        ret.add_widget(EnumWidget::create(Alignment::TOP, 6, 18, game, TileSize::SMALL, 20 ,20),0 ,0);
+        ret.add_widget(EnumWidget::create(Alignment::TOP, 6, 18, TileSize::SMALL, 20 ,20),0 ,0);
 
-        game.current_level = Some(level);
+        ret.build_palette();
 
-        ret.add_test_tiles(&game.tiles);
+        // carry on from the last saved level if there is one
+        game.current_level = Some(Level::create_empty());
+        if Path::new(level_path).exists() {
+            if let Some(level) = ret.read_level() {
+                game.current_level = Some(level);
+            }
+        }
 
         Box::new(ret)
     }
 
-    fn cycle(&mut self, mousex : u32, mousey : u32, events: Vec<Event>) {
+    /// Rebuilds the palette from every tile matching the selected tile size
+    fn build_palette(&mut self) {
+        let mut palette = self.tiles.iter()
+            .filter(|(_, tile)| (*tile).clone().get_size() == self.selected_scale)
+            .map(|(rl, _)| rl.clone())
+            .collect::<Vec<_>>();
+        // sorted so the palette is always in the same order
+        palette.sort();
+
+        self.palette = palette;
+        if self.palette_index >= self.palette.len() {
+            self.palette_index = 0;
+        }
+    }
+
+    /// Move through the palette, wrapping around at either end
+    fn cycle_palette(&mut self, amount : i32) {
+        if !self.palette.is_empty() {
+            let len = self.palette.len() as i32;
+            self.palette_index = (((self.palette_index as i32 + amount) % len + len) % len) as usize;
+        }
+    }
+
+    /// The tile currently selected in the palette
+    pub fn get_selected_tile(&self) -> Option<Tile> {
+        self.palette.get(self.palette_index).and_then(|rl| self.tiles.get(rl)).cloned()
+    }
+
+    /// Sets or removes a tile in the level, returning whatever was there before. The level is changed once the game applies
+    /// the [`Command::SetTile`], so a tile already set this tick is returned from the pending tiles instead of the level
+    fn set_tile(&mut self, ctx : &mut Context, size : TileSize, coords : (i32, i32), tile : Option<Tile>) -> Option<Tile> {
+        let before = match self.pending.iter().rev().find(|(s, c, _)| *s == size && *c == coords) {
+            Some((_, _, pending)) => { pending.clone() }
+            None => { ctx.level.and_then(|level| level.get_graph_ref(size).get_nodes().get(&coords).cloned()) }
+        };
+        self.pending.push((size, coords, tile.clone()));
+        ctx.push(Command::SetTile(size, coords, tile));
+        before
+    }
+
+    /// Place or erase a tile and add it to the undo stack
+    fn edit(&mut self, ctx : &mut Context, size : TileSize, coords : (i32, i32), tile : Option<Tile>) {
+        let before = self.set_tile(ctx, size, coords, tile.clone());
 
+        // nothing changed, so there is nothing to undo
+        if before.as_ref().map(|t| t.get_resource_location().to_string()) == tile.as_ref().map(|t| t.get_resource_location().to_string()) {
+            return
+        }
+
+        self.undo_stack.push(Edit { size, coords, before, after : tile });
+        // a new edit means the old redos no longer make sense
+        self.redo_stack.clear();
+    }
 
+    /// Undo the last edit
+    pub fn undo(&mut self, ctx : &mut Context) {
+        if let Some(edit) = self.undo_stack.pop() {
+            self.set_tile(ctx, edit.size, edit.coords, edit.before.clone());
+            self.redo_stack.push(edit);
+        }
+    }
+
+    /// Redo the last undone edit
+    pub fn redo(&mut self, ctx : &mut Context) {
+        if let Some(edit) = self.redo_stack.pop() {
+            self.set_tile(ctx, edit.size, edit.coords, edit.after.clone());
+            self.undo_stack.push(edit);
+        }
+    }
+
+    /// Save the level being edited to its level path. Saved once the game has applied the tiles set before it
+    pub fn save(&mut self, ctx : &mut Context) {
+        ctx.push(Command::SaveLevel(self.level_path.clone()));
+    }
+
+    /// Reload the level from its level path, throwing away any unsaved changes
+    pub fn load(&mut self, ctx : &mut Context) {
+        // the loaded level replaces anything set before it
+        self.pending.clear();
+        if let Some(level) = self.read_level() {
+            ctx.push(Command::SetLevel(level));
+        }
+    }
+
+    /// Reads the level from its level path, clearing the undo and redo stacks if it could be loaded
+    fn read_level(&mut self) -> Option<Level> {
+        match Level::load(self.level_path.as_str(), &self.tiles) {
+            Ok(level) => {
+                self.undo_stack.clear();
+                self.redo_stack.clear();
+                info!("Loaded level from {}", self.level_path);
+                Some(level)
+            }
+            Err(e) => {
+                warn!("{}", e);
+                None
+            }
+        }
+    }
+
+}
+
+impl Screen for RoomEditorScreen {
+    fn get_widgets(&mut self) -> &mut Vec<Vec<Box<dyn Widget>>> {
+        &mut self.widgets
+    }
+
+    fn get_focus(&mut self) -> &mut Option<(usize, usize)> {
+        &mut self.focus
+    }
+
+    fn uses_focus(&mut self) -> bool {
+        false
+    }
+
+    fn create(game: &mut Game) -> Box<Self>
+    where
+        Self: Sized
+    {
+        Self::create_at(game, EDITOR_LEVEL_PATH)
+    }
+
+    fn cycle(&mut self, mousex : u32, mousey : u32, ctx : &mut Context) {
+
+        // the tiles set last tick are in the level by now
+        self.pending.clear();
 
         let mut scale_indx = 0usize;
+        let mut over_widget = false;
 
         for widgets in self.get_widgets() {
             for w in widgets {
+                w.tick(ctx);
 
                 if w.get_resource_location().to_string() == String::from("game:widgets/enum/tile_size") {
                     scale_indx = w.return_integer_data().unwrap();
@@ -125,72 +253,92 @@ impl Screen for RoomEditorScreen {
 
                 if (coords.0 <= mousex as i32 && coords.0 as u32 + uv.unwrap().width() > mousex) && (coords.1 <= mousey as i32 && coords.1 as u32 + uv.unwrap().height() > mousey) {
                     let _ = w.set_selected(true);
+                    over_widget = true;
                     break
                 }
             }
         }
-        self.selected_scale = TileSize::get_from_index(scale_indx);
-
-        let mut player_coords = unsafe{(*self.game).entities.get_mut((*self.game).player.unwrap()).unwrap().lock().unwrap().get_coords()};
-
-        unsafe {(*self.game).entities.get_mut((*self.game).player.unwrap()).unwrap().lock().unwrap().set_coords((player_coords.0, player_coords.1))}
 
-        player_coords = unsafe{(*self.game).entities.get_mut((*self.game).player.unwrap()).unwrap().lock().unwrap().get_coords()};
+        // the palette only shows tiles of the selected size
+        let scale = TileSize::get_from_index(scale_indx);
+        if scale != self.selected_scale {
+            self.selected_scale = scale;
+            self.build_palette();
+        }
 
-        let px = (if player_coords.0 < 0.0 {player_coords.0 - 1.0} else { player_coords.0 }) as i32;
-        let py = (if player_coords.1 < 0.0 {player_coords.1 - 1.0} else { player_coords.1 }) as i32;
-        let mouse_coord_x = mousex as i32 - (160 - px);
-        let mouse_coord_y = mousey as i32 - (90 - py);
+        // the world space coordinates under the mouse
+        let mouse_coords = ctx.camera.screen_to_world((mousex as i32, mousey as i32));
 
-        let mouse_x_fixed = if mouse_coord_x < 0 {(mouse_coord_x - self.selected_scale.get().0 as i32)/ self.selected_scale.get().0 as i32} else { (mouse_coord_x)/ self.selected_scale.get().0 as i32 };
-        let mouse_y_fixed = if mouse_coord_y < 0 {(mouse_coord_y - self.selected_scale.get().0 as i32)/ self.selected_scale.get().0 as i32} else { (mouse_coord_y)/ self.selected_scale.get().0 as i32 };
+        let tile_size = self.selected_scale.get().0 as i32;
 
+        // the tile space coordinates of the tile under the mouse
+        let mouse_x_fixed = (mouse_coords.0 / tile_size as f32).floor() as i32;
+        let mouse_y_fixed = (mouse_coords.1 / tile_size as f32).floor() as i32;
 
-        let x = (((mouse_x_fixed) + (self.selected_scale.get().0 as i32 * mouse_x_fixed)) - mouse_x_fixed) as f32;
-        let y = (((mouse_y_fixed) + (self.selected_scale.get().0 as i32 * mouse_y_fixed)) - mouse_y_fixed) as f32;
+        let x = (mouse_x_fixed * tile_size) as f32;
+        let y = (mouse_y_fixed * tile_size) as f32;
 
-        unsafe {
-            let mut ass = (*self.game).entities.get_mut(self.highlight_index).unwrap().lock().unwrap().get_asset_data();
-            (*self.game).entities.get_mut(self.highlight_index).unwrap().lock().unwrap().set_asset_data(
+        // the highlight previews the selected tile, or just outlines the tile if the palette is empty
+        let highlight_asset_data = match self.get_selected_tile() {
+            Some(tile) => {
+                let mut ass = tile.get_asset_data();
+                ass.origin = (0, 0);
+                ass
+            }
+            None => {
                 AssetData {
-                    resource_location : ass.resource_location.clone(),
-                    uv : {
-                        let mut rect = ass.clone().uv.unwrap();
-                        rect.set_width(self.selected_scale.get().0);
-                        rect.set_height(self.selected_scale.get().0);
-                        Some(rect)
-                    },
+                    resource_location : ResourceLocation::new("game", "tiles/highlight.png"),
+                    uv : Some(Rect::new(0, 0, tile_size as u32, tile_size as u32)),
                     origin: (0, 0),
                 }
-            );
-            (*self.game).entities.get_mut(self.highlight_index).unwrap().lock().unwrap().set_coords(
-                (
-                    x,
-                    y
-                )
-            )
+            }
+        };
+
+        {
+            let highlight = ctx.entities.get(&self.highlight).unwrap();
+            let mut highlight = highlight.lock().unwrap();
+            highlight.set_asset_data(highlight_asset_data);
+            let _ = highlight.set_coords((x, y));
         }
 
-        for e in events {
+        for e in ctx.events.clone() {
+            let keybindings = ctx.keybindings;
             match e {
-                Event::KeyDown {
-                    keycode: Some(Keycode::B),
+                Event::KeyDown { .. } => {
+                    if keybindings.is_pressed(Action::EditorNextTile, &e) { self.cycle_palette(1) }
+                    else if keybindings.is_pressed(Action::EditorUndo, &e) { self.undo(ctx) }
+                    else if keybindings.is_pressed(Action::EditorRedo, &e) { self.redo(ctx) }
+                    else if keybindings.is_pressed(Action::EditorSave, &e) { self.save(ctx) }
+                    else if keybindings.is_pressed(Action::EditorLoad, &e) { self.load(ctx) }
+                },
+                Event::MouseWheel {
+                    y : wheel,
                     ..
                 } => {
-
+                    self.cycle_palette(-wheel.signum())
                 },
                 Event::MouseButtonDown {
                     mouse_btn : MouseButton::Left,
                     ..
                 } => {
-                    unsafe {(*self.game).current_level.as_mut().unwrap().tile_small.append_from_wolrd_space(
-                        self.tiles.get(&ResourceLocation::new("game", "tiles/cardboard_box.json").to_string()).unwrap().clone(),
-                        (x as i32,y as i32),
-                        vec![])}
+                    // clicking a widget shouldn't place a tile underneath it
+                    if !over_widget {
+                        if let Some(tile) = self.get_selected_tile() {
+                            self.edit(ctx, self.selected_scale, (mouse_x_fixed, mouse_y_fixed), Some(tile))
+                        }
+                    }
+                },
+                Event::MouseButtonDown {
+                    mouse_btn : MouseButton::Right,
+                    ..
+                } => {
+                    if !over_widget {
+                        self.edit(ctx, self.selected_scale, (mouse_x_fixed, mouse_y_fixed), None)
+                    }
                 }
                 _ => {}
             }
         }
 
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,64 @@
+use crate::command::Command;
+use crate::context::Context;
+use crate::game::Game;
+use crate::input::{Action, Binding};
+use crate::screen::Screen;
+use crate::screens::main_menu_screen::MainMenuScreen;
+use crate::widget::{Alignment, Widget};
+use crate::widgets::keybind_widget::KeybindWidget;
+use crate::widgets::text_button_widget::TextButtonWidget;
+
+/// Lists every [`Action`] and the keys bound to it. Clicking an action and pressing a key replaces the first key bound to
+/// it, keeping any others like the arrow keys, which is saved straight away.
+pub struct SettingsScreen {
+    widgets : Vec<Vec<Box<dyn Widget>>>,
+    focus : Option<(usize, usize)>,
+}
+
+impl SettingsScreen {
+
+    // the action waiting for a key press, if any
+    fn listening(&mut self) -> Option<Action> {
+        self.get_widgets().iter_mut().flatten().find_map(|w| w.return_integer_data()).map(|i| Action::ALL[i])
+    }
+}
+
+impl Screen for SettingsScreen {
+    fn get_widgets(&mut self) -> &mut Vec<Vec<Box<dyn Widget>>> {
+        &mut self.widgets
+    }
+
+    fn get_focus(&mut self) -> &mut Option<(usize, usize)> {
+        &mut self.focus
+    }
+
+    fn create(_game: &mut Game) -> Box<Self>
+    where
+        Self: Sized
+    {
+        let mut ret = Self {
+            widgets: vec![],
+            focus: None,
+        };
+        for (i, action) in Action::ALL.iter().enumerate() {
+            ret.add_widget(KeybindWidget::create(*action, Alignment::NONE, 40, 20 + i as i32 * 10), 0, i);
+        }
+        ret.add_widget(TextButtonWidget::create("Back", Alignment::NONE, 40, 160, |ctx| {
+            ctx.push(Command::ChangeScreen(|game| MainMenuScreen::create(game)))
+        }), 0, Action::ALL.len());
+        Box::new(ret)
+    }
+
+    fn captures_keys(&mut self) -> bool {
+        self.listening().is_some()
+    }
+
+    fn tick(&mut self, _mousex: u32, _mousey: u32, ctx: &mut Context) {
+        // bind the first key pressed to the action that was clicked, in place of its first key
+        if let Some(action) = self.listening() {
+            if let Some(binding) = ctx.events.iter().find_map(Binding::from_event) {
+                ctx.push(Command::Rebind(action, 0, binding));
+            }
+        }
+    }
+}
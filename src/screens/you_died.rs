@@ -6,8 +6,8 @@ use crate::widgets::player_health_widget::PlayerHealthWidget;
 use crate::widgets::source_widget::SourceWidget;
 
 pub struct DeathScreen {
-    game : *mut Game,
     widgets : Vec<Vec<Box<dyn Widget>>>,
+    focus : Option<(usize, usize)>,
 }
 
 impl Screen for DeathScreen{
@@ -15,12 +15,8 @@ impl Screen for DeathScreen{
         &mut self.widgets
     }
 
-    fn get_game(&mut self) -> *mut Game {
-        self.game
-    }
-
-    fn set_game(&mut self, game: *mut Game) {
-        self.game = game;
+    fn get_focus(&mut self) -> &mut Option<(usize, usize)> {
+        &mut self.focus
     }
 
     fn create(game: &mut Game) -> Box<Self>
@@ -29,9 +25,9 @@ impl Screen for DeathScreen{
     {
         let mut ret = Self{
             widgets: vec![],
-            game,
+            focus: None,
         };
-        ret.add_widget(DeathMessage::create(Alignment::NONE, 0, 0, game), 0, 0);
+        ret.add_widget(DeathMessage::create(Alignment::NONE, 0, 0, game.score), 0, 0);
         Box::new(ret)
     }
 
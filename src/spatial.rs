@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use crate::collision::Aabb;
+use uuid::Uuid;
+use crate::resource_location::ResourceLocation;
+
+/// The size of each cell in the [`SpatialGrid`], in world space
+pub const CELL_SIZE : f32 = 32.0;
+
+/// A snapshot of an entity's position and hitbox, stored in the [`SpatialGrid`]
+#[derive(Clone, Debug)]
+pub struct SpatialEntry {
+    pub uuid : Uuid,
+    pub coords : (f32, f32),
+    pub hitbox : Aabb,
+    pub resource_location : ResourceLocation
+}
+
+/// A uniform grid of entity hitboxes, used to quickly find the entities in an area without checking all of them. Each entity
+/// is added to every cell its hitbox overlaps.
+pub struct SpatialGrid {
+    cell_size : f32,
+    cells : HashMap<(i32, i32), Vec<usize>>,
+    entries : Vec<SpatialEntry>
+}
+
+impl SpatialGrid {
+
+    /// Create an empty grid given the size of its cells
+    pub fn create(cell_size : f32) -> Self {
+        Self {
+            cell_size,
+            cells : HashMap::new(),
+            entries : vec![]
+        }
+    }
+
+    /// Remove every entry from the grid
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+    }
+
+    /// Add an entity to the grid
+    pub fn insert(&mut self, entry : SpatialEntry) {
+        let id = self.entries.len();
+        for cell in self.cells_in(&entry.hitbox) {
+            self.cells.entry(cell).or_insert(vec![]).push(id);
+        }
+        self.entries.push(entry);
+    }
+
+    /// Every entry in the grid
+    pub fn get_entries(&self) -> &Vec<SpatialEntry> {
+        &self.entries
+    }
+
+    /// The cells an area overlaps
+    fn cells_in(&self, area : &Aabb) -> Vec<(i32, i32)> {
+        let mut cells = vec![];
+        for x in (area.x / self.cell_size).floor() as i32..=(area.right() / self.cell_size).floor() as i32 {
+            for y in (area.y / self.cell_size).floor() as i32..=(area.bottom() / self.cell_size).floor() as i32 {
+                cells.push((x, y));
+            }
+        }
+        cells
+    }
+
+    /// The entries in the same cells as an area, which may or may not actually overlap it
+    fn candidates(&self, area : &Aabb) -> Vec<&SpatialEntry> {
+        let mut ids = self.cells_in(area).iter()
+            .filter_map(|cell| self.cells.get(cell))
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>();
+        // an entity can be in more than one cell
+        ids.sort();
+        ids.dedup();
+        ids.iter().map(|id| &self.entries[*id]).collect()
+    }
+
+    /// The entries whose hitbox overlaps an area. A box with no width or height can be used to check a single point
+    pub fn query(&self, area : &Aabb) -> Vec<&SpatialEntry> {
+        self.candidates(area).into_iter().filter(|e| e.hitbox.intersects(area)).collect()
+    }
+
+    /// The entries whose coordinates are within a radius of a point
+    pub fn within_radius(&self, coords : (f32, f32), radius : f32) -> Vec<&SpatialEntry> {
+        let area = Aabb::centred(coords, (radius * 2.0, radius * 2.0));
+        self.candidates(&area).into_iter().filter(|e| dist_squared(e.coords, coords) <= radius * radius).collect()
+    }
+
+    /// The closest entry to a point with the given resource location
+    pub fn nearest_of_type(&self, coords : (f32, f32), rl : &ResourceLocation) -> Option<&SpatialEntry> {
+        self.entries.iter()
+            .filter(|e| e.resource_location.to_string() == rl.to_string())
+            .min_by(|a, b| dist_squared(a.coords, coords).total_cmp(&dist_squared(b.coords, coords)))
+    }
+
+    /// Every pair of entities whose hitboxes overlap, in the order they were added to the grid
+    pub fn overlapping_pairs(&self) -> Vec<(Uuid, Uuid)> {
+        let mut pairs = vec![];
+        for ids in self.cells.values() {
+            for (i, a) in ids.iter().enumerate() {
+                for b in &ids[i + 1..] {
+                    if self.entries[*a].hitbox.intersects(&self.entries[*b].hitbox) {
+                        // ids in a cell are always in ascending order
+                        pairs.push((*a, *b));
+                    }
+                }
+            }
+        }
+        // entities sharing more than one cell are found more than once
+        pairs.sort();
+        pairs.dedup();
+        pairs.iter().map(|(a, b)| (self.entries[*a].uuid, self.entries[*b].uuid)).collect()
+    }
+}
+
+/// The squared distance between two points, which is enough to compare distances without a square root
+fn dist_squared(a : (f32, f32), b : (f32, f32)) -> f32 {
+    (a.0 - b.0) * (a.0 - b.0) + (a.1 - b.1) * (a.1 - b.1)
+}
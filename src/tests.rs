@@ -3,12 +3,27 @@
 
 #[cfg(test)]
 mod tests {
+    use std::{env, fs};
+    use std::path::Path;
     use std::sync::Mutex;
+    use crate::camera::Camera;
+    use crate::collision::Aabb;
     use crate::entities::dummy::DummyEntity;
+    use crate::command::Command;
     use crate::entity::Entity;
     use crate::game::Game;
     use crate::render::AssetData;
+    use crate::resource_location::ResourceLocation;
     use crate::utils::{mul_vec, normalise_vec};
+    use crate::headless::{HeadlessRunner, ScriptedInput, DEFAULT_DELTA};
+    use crate::screen::Screen;
+    use crate::screens::room_editor_screen::RoomEditorScreen;
+    use crate::level::Level;
+    use crate::tile::TileSize;
+    use crate::timestep::{FixedTimestep, MAX_STEPS, STEP};
+    use sdl2::event::Event;
+    use sdl2::keyboard::{Keycode, Mod, Scancode};
+    use sdl2::mouse::MouseButton;
 
     #[test]
     fn mul_vec_test() {
@@ -59,27 +74,230 @@ mod tests {
 
     #[test]
     fn world_space_screen_space_test() {
-        // create a game instance
-        let mut game = Game::initiate();
         // create an entity
-        let mut dummy = DummyEntity::create(&mut game, AssetData::empty()); // represents entity
+        let mut dummy = DummyEntity::create(AssetData::empty()); // represents entity
 
         // set its coords
         dummy.set_coords((-80f32, 0f32));
 
-        // create player coordinates
-        let player_coords = (0f32, 0f32);
+        // create a camera looking at the origin
+        let camera = Camera::new();
 
         // get relative screen coordinates
-        let mut out = dummy.screen(player_coords);
+        let mut out = dummy.screen(&camera);
 
         assert_eq!(out, (80, 90));
 
         // again for off-screen coordinates
         dummy.set_coords((235f32, -103f32));
 
-        out = dummy.screen(player_coords);
+        out = dummy.screen(&camera);
         assert_eq!(out, (395, -13))
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn camera_test() {
+        let mut camera = Camera::new();
+
+        // screen and world space convert back and forth
+        assert_eq!(camera.world_to_screen((0.0, 0.0)), (160, 90));
+        assert_eq!(camera.screen_to_world((0, 0)), (-160.0, -90.0));
+
+        // following eases towards the target, and snapping doesn't
+        camera.follow((100.0, 0.0), 0.1);
+        assert!(camera.position.0 > 50.0 && camera.position.0 < 100.0);
+        camera.follow_speed = 0.0;
+        camera.follow((100.0, 50.0), 0.1);
+        assert_eq!(camera.centre(), (100.0, 50.0));
+
+        // the camera stays inside its bounds, and in the middle of bounds smaller than the screen
+        camera.set_bounds(Some(Aabb::new(0.0, 0.0, 1000.0, 100.0)));
+        camera.snap_to((0.0, 0.0));
+        assert_eq!(camera.centre(), (160.0, 50.0));
+        camera.snap_to((2000.0, 0.0));
+        assert_eq!(camera.centre(), (840.0, 50.0));
+        assert_eq!(camera.visible_area(), Aabb::new(679.0, -41.0, 322.0, 182.0));
+
+        // shaking moves the view, and calms down over time
+        camera.update(0.05);
+        camera.shake(4.0, 0.5);
+        assert_eq!(camera.get_shake_strength(), 4.0);
+        assert_ne!(camera.centre(), (840.0, 50.0));
+        camera.update(0.25);
+        assert_eq!(camera.get_shake_strength(), 2.0);
+        // a weaker shake doesn't cut a stronger one short
+        camera.shake(1.0, 1.0);
+        assert_eq!(camera.get_shake_strength(), 2.0);
+        camera.update(0.3);
+        assert_eq!(camera.centre(), (840.0, 50.0));
+    }
+
+    #[test]
+    fn headless_demo_level_test() {
+        // run the demo level without a window
+        let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+        runner.load_demo_level();
+
+        // walk right for half a second
+        runner.at(0, ScriptedInput::Hold(Scancode::D));
+        runner.at_time(0.5, ScriptedInput::Release(Scancode::D));
+
+        runner.run_for(2.0);
+        assert_eq!(runner.get_tick(), 120);
+
+        // the player moves at 60 pixels per second, and starts at (16, 80)
+        let coords = runner.game.get_player().unwrap().lock().unwrap().get_coords();
+        assert!((coords.0 - 46.0).abs() < 1.0);
+        assert_eq!(coords.1, 80.0);
+
+        // the turret shoots the player every half a second
+        assert_eq!(runner.player_health(), Some(16.0));
+
+        // player, turret and floaty bomb
+        assert_eq!(runner.entity_count(), 3);
+        assert!((runner.score() - 2.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn fixed_timestep_test() {
+        let mut timestep = FixedTimestep::new(STEP);
+
+        // a fast frame doesn't tick, but is drawn part of the way to the next tick
+        assert_eq!(timestep.advance(STEP * 0.5), 0);
+        assert!((timestep.alpha() - 0.5).abs() < 0.001);
+
+        // the leftover time carries over to the next frame
+        assert_eq!(timestep.advance(STEP * 0.75), 1);
+        assert!((timestep.alpha() - 0.25).abs() < 0.001);
+        assert_eq!(timestep.advance(STEP * 2.0), 2);
+
+        // a stall only catches up so far
+        assert_eq!(timestep.advance(2.0), MAX_STEPS);
+        assert!(timestep.alpha() < 1.0);
+        assert_eq!(timestep.advance(0.0), 0);
+    }
+
+    #[test]
+    fn interpolation_test() {
+        let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+        runner.load_demo_level();
+        runner.at(0, ScriptedInput::Hold(Scancode::D));
+        runner.run_for(0.5);
+
+        // drawn between where the player was before the last tick and where it is now
+        let player = runner.game.player.unwrap();
+        let current = runner.game.get_player().unwrap().lock().unwrap().get_coords();
+        assert_eq!(runner.game.get_render_coords(&player), Some(current));
+        runner.game.interpolation = 0.5;
+        let coords = runner.game.get_render_coords(&player).unwrap();
+        assert!((coords.0 - (current.0 - 0.5)).abs() < 0.01);
+        assert_eq!(coords.1, current.1);
+
+        // the same inputs always give the same result
+        let mut other = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+        other.load_demo_level();
+        other.at(0, ScriptedInput::Hold(Scancode::D));
+        other.run_for(0.5);
+        assert_eq!(other.game.get_player().unwrap().lock().unwrap().get_coords(), current);
+        assert_eq!(other.player_health(), runner.player_health());
+    }
+
+    #[test]
+    fn command_queue_test() {
+        let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+        runner.load_demo_level();
+        runner.step();
+
+        // replace the turret with an explosion
+        let turret = runner.game.entities.uuids()[1];
+        runner.game.commands.push(Command::Despawn(turret));
+        runner.game.commands.push(Command::Spawn(ResourceLocation::new("game", "entity/explosion"), (128.0, 36.0)));
+
+        // nothing changes until the commands are applied
+        assert_eq!(runner.entity_count(), 3);
+        assert!(runner.game.entities.contains(&turret));
+        runner.game.apply_commands();
+        assert!(runner.game.commands.is_empty());
+        assert!(!runner.game.entities.contains(&turret));
+        assert_eq!(runner.entity_count(), 3);
+
+        // the explosion removes itself once its animation finishes
+        runner.run_for(1.0);
+        assert_eq!(runner.entity_count(), 2);
+
+        // game over is applied after everything else, so the spawned entity doesn't survive it
+        runner.game.commands.push(Command::GameOver);
+        runner.game.commands.push(Command::Spawn(ResourceLocation::new("game", "entity/turret"), (128.0, 36.0)));
+        runner.step();
+        assert_eq!(runner.entity_count(), 0);
+        assert!(runner.game.current_level.is_none());
+        assert_eq!(runner.player_health(), None);
+    }
+
+    fn click(button : MouseButton) -> ScriptedInput {
+        ScriptedInput::Event(Event::MouseButtonDown { timestamp: 0, window_id: 0, which: 0, mouse_btn: button, clicks: 1, x: 0, y: 0 })
+    }
+
+    fn ctrl_key(key : Keycode, scancode : Scancode) -> ScriptedInput {
+        ScriptedInput::Event(Event::KeyDown { timestamp: 0, window_id: 0, keycode: Some(key), scancode: Some(scancode), keymod: Mod::LCTRLMOD, repeat: false })
+    }
+
+    #[test]
+    fn room_editor_undo_test() {
+        // start from an empty level, away from the game's own data
+        let path = env::temp_dir().join("room_editor_undo_test.json");
+        let path = path.to_str().unwrap();
+        let _ = fs::remove_file(path);
+
+        let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+        runner.game.current_screen = Some(RoomEditorScreen::create_at(&mut runner.game, path));
+
+        // the small tile at (0, 0) is 8 pixels right and down of the centre of the screen
+        let get_tile = |runner : &mut HeadlessRunner| runner.game.current_level.as_mut().unwrap()
+            .get_graph(TileSize::SMALL).get_nodes().get(&(0, 0)).map(|t| t.get_resource_location().to_string());
+        let before = get_tile(&mut runner);
+
+        runner.at(0, ScriptedInput::MoveMouse(168, 98));
+        runner.at(1, click(MouseButton::Left));
+        runner.step();
+        runner.step();
+
+        // the first small tile in the palette is placed
+        let placed = get_tile(&mut runner);
+        assert_eq!(placed, Some(String::from("game:tiles/cardboard_box.json")));
+
+        runner.at(2, ctrl_key(Keycode::Z, Scancode::Z));
+        runner.step();
+        assert_eq!(get_tile(&mut runner), before);
+
+        runner.at(3, ctrl_key(Keycode::Y, Scancode::Y));
+        runner.step();
+        assert_eq!(get_tile(&mut runner), placed);
+
+        // right click erases
+        runner.at(4, click(MouseButton::Right));
+        runner.step();
+        assert_eq!(get_tile(&mut runner), None);
+
+        // placing and erasing the same tile in one tick undoes one at a time
+        runner.at(5, click(MouseButton::Left));
+        runner.at(5, click(MouseButton::Right));
+        runner.step();
+        assert_eq!(get_tile(&mut runner), None);
+        runner.at(6, ctrl_key(Keycode::Z, Scancode::Z));
+        runner.step();
+        assert_eq!(get_tile(&mut runner), placed);
+        runner.at(7, ctrl_key(Keycode::Z, Scancode::Z));
+        runner.step();
+        assert_eq!(get_tile(&mut runner), None);
+
+        // saving writes to the given path, not the game's data, including a tile placed in the same tick
+        runner.at(8, click(MouseButton::Left));
+        runner.at(8, ctrl_key(Keycode::S, Scancode::S));
+        runner.step();
+        assert!(Path::new(path).exists());
+        let mut saved = Level::load(path, &runner.game.tiles).unwrap();
+        assert_eq!(saved.get_graph(TileSize::SMALL).get_nodes().get(&(0, 0)).map(|t| t.get_resource_location().to_string()), placed);
+    }
+
+}
@@ -1,8 +1,9 @@
 use std::cmp::PartialEq;
-use std::collections::HashMap;
 use sdl2::keyboard::Scancode::S;
 use sdl2::rect::Rect;
-use sdl2::render::{Canvas, Texture, WindowCanvas};
+use sdl2::render::Canvas;
+use crate::camera::Camera;
+use crate::render_target::RenderTarget;
 use crate::render;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
@@ -76,6 +77,15 @@ impl TileSize {
         }
     }
 
+    /// The name of the TileSize, as read by [`TileSize::parse`] and written to level files
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            TileSize::BIG => {"big"}
+            TileSize::MEDIUM => {"medium"}
+            TileSize::SMALL => {"small"}
+        }
+    }
+
     /// Get the dimensions of the TileSize. Returns a tuple - pretty dumb but its on the todo list to fix
     pub fn get(&self) -> (u32, u32) {
         match self {
@@ -91,11 +101,7 @@ impl WidgetEnum for TileSize {
 
     // gets each enum field as a string - matching the strings required for parse
     fn get_as_string(&mut self) -> String {
-        match self {
-            TileSize::BIG => {String::from("big")}
-            TileSize::MEDIUM => {String::from("medium")}
-            TileSize::SMALL => {String::from("small")}
-        }
+        self.get_name().to_string()
     }
 
     fn get_from_index(index: usize) -> Self {
@@ -139,7 +145,9 @@ pub struct Tile {
     origin : (i32, i32),
     collision : bool,
     collision_box : Option<(u32, u32)>,
-    asset_data : AssetData
+    asset_data : AssetData,
+    /// How expensive the tile is to walk over when path-finding, 1 is the cheapest
+    cost : u32
 }
 
 impl PartialEq for TileSize {
@@ -159,7 +167,8 @@ impl Tile {
         size : TileSize,
         origin : (i32, i32),
         collision : bool,
-        collision_box : Option<(u32, u32)>
+        collision_box : Option<(u32, u32)>,
+        cost : u32
     ) -> Self {
 
         let s = size.get();
@@ -178,7 +187,8 @@ impl Tile {
             origin,
             collision,
             collision_box,
-            asset_data : ass
+            asset_data : ass,
+            cost : cost.max(1)
         }
     }
 
@@ -201,6 +211,7 @@ impl Tile {
             collision: false,
             collision_box: None,
             asset_data: ass,
+            cost : 1,
         }
 
     }
@@ -223,26 +234,14 @@ impl Tile {
             collision: false,
             collision_box: None,
             asset_data: ass,
+            cost : 1,
         }
 
     }
 
-    fn screen(&self, coords : (i32, i32), player_coords :  (f32, f32)) -> (i32, i32) {
-
-        let dims = render::get_actual_dimensions().lock().unwrap().get();
-        let half_x = (dims.0 / 2) as i32;
-        let half_y = (dims.1 / 2) as i32;
-
-        let x = coords.0;
-        let y = coords.1;
-        let px = (if player_coords.0 < 0.0 {player_coords.0 - 1.0} else { player_coords.0 }) as i32;
-        let py = (if player_coords.1 < 0.0 {player_coords.1 - 1.0} else { player_coords.1 }) as i32;
-        ((half_x - px) + x, (half_y - py ) + y)
-    }
-
-    pub fn render(&self, texture: &HashMap<String,Texture>, coords : (i32, i32), canvas: &mut WindowCanvas, sf : i32, player_coords :  (f32, f32)) {
-        let screen = self.screen(coords, player_coords);
-        render::draw_pp_texture(screen.0, screen.1, &self.asset_data, canvas, sf, texture)
+    pub fn render(&self, coords : (i32, i32), target : &mut dyn RenderTarget, sf : i32, camera : &Camera) {
+        let screen = camera.world_to_screen((coords.0 as f32, coords.1 as f32));
+        render::draw_pp_texture(screen.0, screen.1, &self.asset_data, target, sf)
 
     }
 
@@ -250,7 +249,7 @@ impl Tile {
         self.name.clone()
     }
 
-    pub fn get_resource_location(&mut self) -> ResourceLocation {
+    pub fn get_resource_location(&self) -> ResourceLocation {
         self.resource_location.clone()
     }
 
@@ -262,6 +261,27 @@ impl Tile {
         self.tile_type.clone()
     }
 
+    pub fn get_origin(&self) -> (i32, i32) {
+        self.origin
+    }
+
+    /// The size of the tile's collision box, or None if the tile isn't solid
+    pub fn get_collision_box(&self) -> Option<(u32, u32)> {
+        if self.collision { self.collision_box } else { None }
+    }
+
+    pub fn get_asset_data(&self) -> AssetData {
+        self.asset_data.clone()
+    }
+
+    pub fn get_cost(&self) -> u32 {
+        self.cost
+    }
+
+    pub fn set_cost(&mut self, cost : u32) {
+        self.cost = cost.max(1)
+    }
+
 }
 
 impl Clone for Tile {
@@ -275,6 +295,7 @@ impl Clone for Tile {
             collision: self.collision.clone(),
             collision_box: self.collision_box.clone(),
             asset_data: self.asset_data.clone(),
+            cost: self.cost,
         }
     }
 }
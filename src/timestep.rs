@@ -0,0 +1,53 @@
+/// How long each tick of the simulation lasts, 60 ticks a second
+pub const STEP : f32 = 1.0 / 60.0;
+
+/// The most ticks that are run to catch up in a single frame. Any more time than this is dropped, so a long stall slows
+/// the game down for a moment instead of running hundreds of ticks at once
+pub const MAX_STEPS : u32 = 5;
+
+/// Splits the time between frames into ticks of the same length, so physics and timers behave the same no matter the
+/// frame rate. Whatever time is left over is carried on to the next frame, and is used to draw the game part of the way
+/// between the last two ticks, see [`FixedTimestep::alpha`].
+pub struct FixedTimestep {
+    /// How long each tick lasts, in seconds
+    pub step : f32,
+    /// The most ticks that are run in one frame
+    pub max_steps : u32,
+    // time that hasn't been ticked yet
+    accumulator : f32
+}
+
+impl FixedTimestep {
+
+    /// Create a timestep ticking every `step` seconds, catching up at most [`MAX_STEPS`] ticks a frame
+    pub fn new(step : f32) -> Self {
+        Self {
+            step,
+            max_steps : MAX_STEPS,
+            accumulator : 0.0
+        }
+    }
+
+    /// Add the time a frame took, and return how many ticks should be run for it
+    pub fn advance(&mut self, elapsed : f32) -> u32 {
+        self.accumulator += elapsed.max(0.0);
+
+        let mut steps = 0;
+        while self.accumulator >= self.step && steps < self.max_steps {
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+
+        // too far behind to catch up, so drop the rest rather than falling further behind every frame
+        if self.accumulator >= self.step {
+            self.accumulator %= self.step;
+        }
+        steps
+    }
+
+    /// How far between the last tick and the next the current time is, from 0 to 1. Rendering eases between the last two
+    /// ticks by this amount
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.step).clamp(0.0, 1.0)
+    }
+}
@@ -12,10 +12,12 @@ use num::integer::{sqrt, Roots};
 use num::pow;
 use uuid::Uuid;
 use crate::entity::{Entity,};
+use crate::entity_list::EntityList;
 
 /// Multiply a 2D vector represented using a tuple by a number.
 /// # Example
 /// ```
+/// # use nea_project::utils::mul_vec;
 /// let mut vector = (3.5, 6.0);
 /// mul_vec(&mut vector, 4.0);
 /// assert_eq!(vector, (14.0, 24.0))
@@ -39,11 +41,11 @@ pub fn normalise_vec(vec : (f32, f32)) -> (f32,f32) {
 
 
 /// Z-Ordering for entities. Returns a list of indexes for rendering entities in the correct order.
-pub(crate) fn order_sort(entities : &mut Vec<Box<Mutex<dyn Entity>>>) -> Vec<(usize, usize, f32)> {
+pub(crate) fn order_sort(entities : &EntityList) -> Vec<(usize, usize, f32)> {
     //              list   index  amount
     let mut list : Vec<(usize, usize, f32)> = vec![];
     let mut iter = 0usize;
-    for s in entities {
+    for (_, s) in entities.iter() {
         list.push((0usize, iter, s.lock().unwrap().get_coords().1) );
         iter+=1;
     }
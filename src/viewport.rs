@@ -0,0 +1,54 @@
+use sdl2::rect::Rect;
+use crate::render::TARGET_DIMENSIONS;
+
+/// Where the game screen is drawn in the window. The game is always [`TARGET_DIMENSIONS`] in size, scaled up by a whole
+/// number so every pixel stays square, and centred in the window. Whatever is left over is left as black bars, so
+/// the game looks the same on any monitor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    /// The size of the window, in actual pixels
+    pub window : (u32, u32),
+    /// How much the game screen is scaled up by
+    pub sf : i32,
+    /// The area of the window the game is drawn into, in actual pixels
+    pub rect : Rect
+}
+
+impl Viewport {
+
+    /// The largest scale the game fits into a window at, letterboxed in the middle of it. The scale is never less than 1,
+    /// so a window smaller than the game cuts the edges off instead
+    pub fn fit(window : (u32, u32)) -> Self {
+        let sf = (window.0 / TARGET_DIMENSIONS.0).min(window.1 / TARGET_DIMENSIONS.1).max(1);
+        let (w, h) = (TARGET_DIMENSIONS.0 * sf, TARGET_DIMENSIONS.1 * sf);
+        Self {
+            window,
+            sf : sf as i32,
+            rect : Rect::new((window.0 as i32 - w as i32) / 2, (window.1 as i32 - h as i32) / 2, w, h)
+        }
+    }
+
+    /// A window made to fit the game at a chosen scale, with no bars
+    pub fn windowed(sf : i32) -> Self {
+        let sf = sf.max(1) as u32;
+        Self::fit((TARGET_DIMENSIONS.0 * sf, TARGET_DIMENSIONS.1 * sf))
+    }
+
+    /// The size of the game screen, before it is scaled up
+    pub fn get_dimensions(&self) -> (u32, u32) {
+        TARGET_DIMENSIONS
+    }
+
+    /// Converts a point in the window, such as the mouse position, to a point on the game screen. Points over the black
+    /// bars are moved to the nearest edge of the screen
+    pub fn to_game(&self, coords : (i32, i32)) -> (u32, u32) {
+        let x = (coords.0 - self.rect.x()).div_euclid(self.sf).clamp(0, TARGET_DIMENSIONS.0 as i32 - 1);
+        let y = (coords.1 - self.rect.y()).div_euclid(self.sf).clamp(0, TARGET_DIMENSIONS.1 as i32 - 1);
+        (x as u32, y as u32)
+    }
+
+    /// Converts a point on the game screen to a point in the window
+    pub fn to_window(&self, coords : (i32, i32)) -> (i32, i32) {
+        (self.rect.x() + coords.0 * self.sf, self.rect.y() + coords.1 * self.sf)
+    }
+}
@@ -1,8 +1,7 @@
-use std::collections::HashMap;
 use log::warn;
 use sdl2::rect::Rect;
-use sdl2::render::{Texture, WindowCanvas};
-use crate::game::Game;
+use crate::render_target::RenderTarget;
+use crate::context::Context;
 use crate::render;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
@@ -12,7 +11,10 @@ pub trait Widget {
 
     /// The action to run when the widget is clicked
     #[must_use]
-    fn on_click(&mut self);
+    fn on_click(&mut self, ctx : &mut Context);
+
+    /// Called every frame by the screen holding the widget, to keep anything it displays from the game up to date
+    fn tick(&mut self, ctx : &Context) {}
 
     /// Get if the widget is being hovered over by the mouse
     #[must_use]
@@ -90,10 +92,6 @@ pub trait Widget {
     #[must_use]
     fn set_allignment(&mut self, alignment: Alignment);
 
-    /// Useless
-    #[must_use]
-    fn get_game(&mut self);
-
     /// Returns data in an integer form. If the data can be presented as an integer, this method can be used to extract said data.
     /// This is used by WidgetEnum, but could be used to return a string as an integer to then be serialised
     fn return_integer_data(&mut self) -> Option<usize> {
@@ -101,12 +99,12 @@ pub trait Widget {
     }
 
     /// Renders the widget to the screen, with the debug texture behind it should ``debug`` be true
-    fn render(&mut self, textures : &HashMap<String, Texture>, sf : i32, canvas : &mut WindowCanvas, debug : bool) {
+    fn render(&mut self, sf : i32, target : &mut dyn RenderTarget, debug : bool) {
         let coords = self.correct_coords();
         if debug {
-            render::draw_pp_texture(coords.0, coords.1, &self.get_debug_asset_data(), canvas, sf, textures)
+            render::draw_pp_texture(coords.0, coords.1, &self.get_debug_asset_data(), target, sf)
         }
-        render::draw_pp_texture(coords.0, coords.1, &self.get_asset_data(), canvas, sf, textures)
+        render::draw_pp_texture(coords.0, coords.1, &self.get_asset_data(), target, sf)
     }
 }
 
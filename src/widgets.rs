@@ -1,12 +1,14 @@
-pub(crate) mod source_widget;
-pub(crate) mod play_widget;
-pub(crate) mod editor_widget;
-pub(crate) mod enum_widget;
-pub(crate) mod err_widget;
-pub(crate) mod player_health_widget;
-pub(crate) mod death_message;
-pub(crate) mod score_widget;
-pub(crate) mod quit_widget;
+pub mod source_widget;
+pub mod play_widget;
+pub mod editor_widget;
+pub mod enum_widget;
+pub mod err_widget;
+pub mod player_health_widget;
+pub mod death_message;
+pub mod score_widget;
+pub mod quit_widget;
+pub mod text_button_widget;
+pub mod keybind_widget;
 
 use crate::widget::Widget;
 
@@ -5,7 +5,7 @@ use std::io::Write;
 use num::clamp;
 use sdl2::rect::Rect;
 use sdl2::render::{Texture, WindowCanvas};
-use crate::game::Game;
+use crate::context::Context;
 use crate::render;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
@@ -17,17 +17,17 @@ pub struct DeathMessage {
     asset_data: AssetData,
     alignment: Alignment,
     coords : (i32, i32),
-    game : *mut Game,
 }
 
 impl DeathMessage {
-    pub fn create(alignment: Alignment, x : i32, y : i32, game : *mut Game) -> Box<Self>
+    /// Create the widget, saving the score the player died with to the scores folder
+    pub fn create(alignment: Alignment, x : i32, y : i32, score : f32) -> Box<Self>
     where
         Self: Sized
     {
         fs::create_dir("./scores");
         let mut file = File::create(format!("./scores/{}.txt", chrono::offset::Local::now().to_string().replace(" ", "_").replace(":", "-"))).unwrap();
-        unsafe{file.write_all(format!("{}",(*game).score as i32).as_bytes()).unwrap()};
+        file.write_all(format!("{}",score as i32).as_bytes()).unwrap();
 
         let ret = Self {
             selected: false,
@@ -38,14 +38,13 @@ impl DeathMessage {
             },
             alignment,
             coords: (x, y),
-            game
         };
         Box::new(ret)
     }
 }
 
 impl Widget for DeathMessage {
-    fn on_click(&mut self) {}
+    fn on_click(&mut self, ctx: &mut Context) {}
 
     fn get_selected(&mut self) -> bool {
         false
@@ -82,9 +81,6 @@ impl Widget for DeathMessage {
         self.alignment = alignment
     }
 
-    // again why is this here what was I on??
-    fn get_game(&mut self) {
-    }
 
 
 }
\ No newline at end of file
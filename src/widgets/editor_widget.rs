@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use sdl2::keyboard::Keycode::N;
 use sdl2::rect::Rect;
-use crate::game::Game;
+use crate::command::Command;
+use crate::context::Context;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
 use crate::widget::Alignment;
@@ -16,7 +17,6 @@ pub struct EditorWidget {
     asset_data_selected : AssetData,
     alignment: Alignment,
     coords : (i32, i32),
-    game : *mut Game
 }
 
 impl EditorWidget {
@@ -31,7 +31,7 @@ impl EditorWidget {
         }
     }
      */
-    pub fn create(alignment: Alignment, x : i32, y : i32, game : *mut Game) -> Box<Self>
+    pub fn create(alignment: Alignment, x : i32, y : i32) -> Box<Self>
     where
         Self: Sized
     {
@@ -50,15 +50,15 @@ impl EditorWidget {
             },
             alignment,
             coords: (x, y),
-            game
         };
         Box::new(ret)
     }
 }
 
 impl Widget for EditorWidget {
-    fn on_click(&mut self) {
-        unsafe{(*self.game).current_screen = Some(RoomEditorScreen::create(&mut *self.game))}
+    fn on_click(&mut self, ctx: &mut Context) {
+        // this is run whilst the current screen is being iterated through, so it can't be replaced here
+        ctx.push(Command::ChangeScreen(|game| RoomEditorScreen::create(game)))
         //(*self.game).unwrap().current_screen = None;
     }
 
@@ -104,9 +104,6 @@ impl Widget for EditorWidget {
         self.alignment = alignment;
     }
 
-    fn get_game(&mut self) {
-        self.game;
-    }
 
 
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
 use log::warn;
 use sdl2::keyboard::Keycode::N;
 use sdl2::rect::Rect;
-use crate::game::Game;
+use crate::context::Context;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
 use crate::widget::{Alignment, Widget};
@@ -15,7 +15,6 @@ pub struct EnumWidget<T : WidgetEnum> {
     coords : (i32, i32),
     asset_data: AssetData,
     asset_data_selected : AssetData,
-    game : *mut Game,
 }
 
 impl<T : WidgetEnum + 'static + Clone> EnumWidget<T> {
@@ -24,7 +23,7 @@ impl<T : WidgetEnum + 'static + Clone> EnumWidget<T> {
         self.enum_type.clone()
     }
 
-    pub fn create(alignment: Alignment, x: i32, y: i32, game: *mut Game, enumt : T, h : u32, w : u32) -> Box<dyn Widget> {
+    pub fn create(alignment: Alignment, x: i32, y: i32, enumt : T, h : u32, w : u32) -> Box<dyn Widget> {
         let ret = Self {
             enum_type:enumt ,
             current_indx: 0,
@@ -41,7 +40,6 @@ impl<T : WidgetEnum + 'static + Clone> EnumWidget<T> {
             },
             alignment,
             coords: (x, y),
-            game
 
         };
         Box::new(ret)
@@ -51,7 +49,7 @@ impl<T : WidgetEnum + 'static + Clone> EnumWidget<T> {
 impl<T : WidgetEnum> Widget for EnumWidget<T> {
 
     /// Cycle through the enum values when clicked
-    fn on_click(&mut self) {
+    fn on_click(&mut self, ctx: &mut Context) {
         // If on the last field, when clicked loop back to the first one
         if self.current_indx == self.enum_type.count() -1 {
             self.current_indx = 0;
@@ -114,9 +112,6 @@ impl<T : WidgetEnum> Widget for EnumWidget<T> {
         self.alignment = alignment
     }
 
-    fn get_game(&mut self) {
-        // why is there a getter that returns nothing...? what was I on
-    }
 
     fn return_integer_data(&mut self) -> Option<usize> {
         Some(self.current_indx)
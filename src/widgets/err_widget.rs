@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use log::{error, warn};
 use sdl2::rect::Rect;
-use crate::game::Game;
+use crate::context::Context;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
 use crate::widget::Alignment;
@@ -13,7 +13,6 @@ pub struct ErrWidget {
     asset_data_selected : AssetData,
     alignment: Alignment,
     coords : (i32, i32),
-    game : Option<*mut Game>
 }
 
 impl ErrWidget {
@@ -24,7 +23,6 @@ impl ErrWidget {
             asset_data_selected : AssetData::empty(),
             alignment : Alignment::NONE,
             coords : (0,0),
-            game: None,
         }
     }
 
@@ -41,7 +39,7 @@ impl ErrWidget {
 }
 
 impl ErrWidget {
-    pub fn create(alignment: Alignment, x : i32, y : i32, game : *mut Game) -> Box<Self>
+    pub fn create(alignment: Alignment, x : i32, y : i32) -> Box<Self>
     where
         Self: Sized
     {
@@ -60,14 +58,13 @@ impl ErrWidget {
             },
             alignment,
             coords: (x, y),
-            game : Some(game)
         };
         Box::new(ret)
     }
 }
 
 impl Widget for ErrWidget {
-    fn on_click(&mut self) {
+    fn on_click(&mut self, ctx: &mut Context) {
         error!("you flipped up buddy");
     }
 
@@ -112,8 +109,5 @@ impl Widget for ErrWidget {
         self.alignment = alignment;
     }
 
-    fn get_game(&mut self) {
-        self.game.unwrap();
-    }
 
 }
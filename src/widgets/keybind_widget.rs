@@ -0,0 +1,113 @@
+use sdl2::rect::Rect;
+use crate::context::Context;
+use crate::input::{Action, Binding};
+use crate::render;
+use crate::render::{AssetData, FONT_SIZE};
+use crate::render_target::RenderTarget;
+use crate::resource_location::ResourceLocation;
+use crate::widget::{Alignment, Widget};
+
+/// How far across the widget the keys are drawn, after the name of the action
+const KEYS_OFFSET : i32 = 108;
+
+/// A row of the settings screen showing the keys bound to an action. Clicking it listens for the next key press, which
+/// the [`SettingsScreen`] binds to the action.
+///
+/// [`SettingsScreen`]: crate::screens::settings_screen::SettingsScreen
+pub struct KeybindWidget {
+    selected : bool,
+    action : Action,
+    listening : bool,
+    // the keys currently bound to the action, kept up to date every tick
+    keys : String,
+    alignment: Alignment,
+    coords : (i32, i32)
+}
+
+impl KeybindWidget {
+    pub fn create(action : Action, alignment: Alignment, x : i32, y : i32) -> Box<Self>
+    where
+        Self: Sized
+    {
+        Box::new(Self {
+            selected: false,
+            action,
+            listening: false,
+            keys: String::new(),
+            alignment,
+            coords: (x, y)
+        })
+    }
+}
+
+impl Widget for KeybindWidget {
+    fn on_click(&mut self, _ctx: &mut Context) {
+        self.listening = true
+    }
+
+    fn tick(&mut self, ctx: &Context) {
+        self.keys = ctx.keybindings.get(self.action).iter().map(|b| b.get_name()).collect::<Vec<_>>().join(" / ");
+
+        // the screen has already taken the key press, see SettingsScreen::tick
+        if self.listening && ctx.events.iter().any(|e| Binding::from_event(e).is_some()) {
+            self.listening = false
+        }
+    }
+
+    fn get_selected(&mut self) -> bool {
+        self.selected
+    }
+
+    fn set_selected(&mut self, tf: bool) {
+        self.selected = tf
+    }
+
+    fn get_screen_coordinates(&mut self) -> (i32, i32) {
+        self.coords
+    }
+
+    fn set_screen_coordinates(&mut self, x: i32, y: i32) {
+        self.coords = (x, y)
+    }
+
+    // only used for the size of the row, the text is drawn in render
+    fn get_asset_data(&mut self) -> AssetData {
+        AssetData {
+            uv: Some(Rect::new(0, 0, KEYS_OFFSET as u32 + render::text_width(&self.keys).max(FONT_SIZE.0 * 12), FONT_SIZE.1)),
+            origin: (0, 0),
+            resource_location: ResourceLocation::new("game", "gui/widgets/debug_background.png"),
+        }
+    }
+
+    fn set_asset_data(&mut self, _ass: AssetData) {}
+
+    fn get_resource_location(&mut self) -> ResourceLocation {
+        ResourceLocation::new("game", "widgets/keybind")
+    }
+
+    fn get_allignment(&mut self) -> Alignment {
+        self.alignment.clone()
+    }
+
+    fn set_allignment(&mut self, alignment: Alignment) {
+        self.alignment = alignment
+    }
+
+    /// The index of the action in [`Action::ALL`] whilst waiting for a key press, otherwise None
+    fn return_integer_data(&mut self) -> Option<usize> {
+        if self.listening { Some(self.action.get_index()) } else { None }
+    }
+
+    fn render(&mut self, sf: i32, target: &mut dyn RenderTarget, debug: bool) {
+        let coords = self.correct_coords();
+        if debug {
+            render::draw_pp_texture(coords.0, coords.1, &self.get_debug_asset_data(), target, sf)
+        }
+        if self.selected || self.listening {
+            render::draw_text(coords.0 - FONT_SIZE.0 as i32, coords.1, ">", target, sf)
+        }
+        render::draw_text(coords.0, coords.1, &self.action.get_name().replace('_', " "), target, sf);
+        let keys = if self.listening { "Press a key..." } else { self.keys.as_str() };
+        render::draw_text(coords.0 + KEYS_OFFSET, coords.1, keys, target, sf)
+    }
+}
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 use sdl2::keyboard::Keycode::N;
 use sdl2::rect::Rect;
-use crate::game::{DyslexiaMode, Game};
+use crate::command::Command;
+use crate::context::Context;
+use crate::game::DyslexiaMode;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
 use crate::screen::Screen;
@@ -22,7 +24,7 @@ pub struct PlayWidget {
     asset_data_selected_dyslexia : AssetData,
     alignment: Alignment,
     coords : (i32, i32),
-    game : *mut Game
+    dyslexia_mode : DyslexiaMode
 }
 
 impl PlayWidget {
@@ -38,7 +40,7 @@ impl PlayWidget {
     }
      */
 
-    pub fn create(alignment: Alignment, x : i32, y : i32, game : *mut Game) -> Box<Self>
+    pub fn create(alignment: Alignment, x : i32, y : i32) -> Box<Self>
     where
         Self: Sized
     {
@@ -67,19 +69,26 @@ impl PlayWidget {
             },
             alignment,
             coords: (x, y),
-            game
+            dyslexia_mode : DyslexiaMode::OFF
         };
         Box::new(ret)
     }
 }
 
 impl Widget for PlayWidget {
-    fn on_click(&mut self) {
-        unsafe{(*self.game).load_demo_level()}
-        unsafe{(*self.game).current_screen = Some(HudScreen::create(&mut *self.game))}
+    fn on_click(&mut self, ctx: &mut Context) {
+        // this is run whilst the current screen is being iterated through, so it can't be replaced here
+        ctx.push(Command::ChangeScreen(|game| {
+            game.load_demo_level();
+            HudScreen::create(game)
+        }))
         //(*self.game).unwrap().current_screen = None;
     }
 
+    fn tick(&mut self, ctx: &Context) {
+        self.dyslexia_mode = ctx.dyslexia_mode.clone();
+    }
+
     fn get_selected(&mut self) -> bool {
         self.selected
     }
@@ -98,7 +107,7 @@ impl Widget for PlayWidget {
 
     fn get_asset_data(&mut self) -> AssetData {
 
-        let dyslexia = unsafe { &mut *self.game }.dyslexia_mode.clone();
+        let dyslexia = self.dyslexia_mode.clone();
 
         match dyslexia {
             DyslexiaMode::ON => {
@@ -139,9 +148,6 @@ impl Widget for PlayWidget {
         self.alignment = alignment;
     }
 
-    fn get_game(&mut self) {
-        self.game;
-    }
 
 
 }
\ No newline at end of file
@@ -1,7 +1,6 @@
-use std::collections::HashMap;
 use sdl2::rect::Rect;
-use sdl2::render::{Texture, WindowCanvas};
-use crate::game::Game;
+use crate::render_target::RenderTarget;
+use crate::context::Context;
 use crate::render;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
@@ -13,12 +12,12 @@ pub struct PlayerHealthWidget {
     asset_data_selected : AssetData,
     alignment: Alignment,
     coords : (i32, i32),
-    game : *mut Game,
-    half : bool
+    half : bool,
+    health : f32
 }
 
 impl PlayerHealthWidget {
-    pub fn create(alignment: Alignment, x : i32, y : i32, game : *mut Game) -> Box<Self>
+    pub fn create(alignment: Alignment, x : i32, y : i32) -> Box<Self>
     where
         Self: Sized
     {
@@ -37,15 +36,21 @@ impl PlayerHealthWidget {
             },
             alignment,
             coords: (x, y),
-            game,
-            half : false
+            half : false,
+            health : 0.0
         };
         Box::new(ret)
     }
 }
 
 impl Widget for PlayerHealthWidget {
-    fn on_click(&mut self) {}
+    fn on_click(&mut self, ctx: &mut Context) {}
+
+    fn tick(&mut self, ctx: &Context) {
+        if let Some(player) = ctx.get_player() {
+            self.health = player.lock().unwrap().get_health();
+        }
+    }
 
     fn get_selected(&mut self) -> bool {
         false
@@ -87,13 +92,9 @@ impl Widget for PlayerHealthWidget {
         self.alignment = alignment
     }
 
-    // again why is this here what was I on??
-    fn get_game(&mut self) {
-    }
 
-    fn render(&mut self, textures: &HashMap<String, Texture>, sf: i32, canvas: &mut WindowCanvas, debug : bool) {
-        let game = unsafe { &mut *self.game };
-        let health = f32::max(game.get_player().unwrap().get_mut().unwrap().get_health(), 0.0);
+    fn render(&mut self, sf : i32, target : &mut dyn RenderTarget, debug : bool) {
+        let health = f32::max(self.health, 0.0);
         let coords = self.correct_coords();
         self.half = false;
         if health > 0.0 {
@@ -102,9 +103,9 @@ impl Widget for PlayerHealthWidget {
                     self.half = true;
                 }
                 if debug {
-                    render::draw_pp_texture(coords.0 - (14 * (h+1)) as i32, coords.1, &Widget::get_debug_asset_data(self), canvas, sf, textures)
+                    render::draw_pp_texture(coords.0 - (14 * (h+1)) as i32, coords.1, &Widget::get_debug_asset_data(self), target, sf)
                 }
-                render::draw_pp_texture(coords.0 - (14 * (h+1)) as i32, coords.1, &self.get_asset_data(), canvas, sf, textures)
+                render::draw_pp_texture(coords.0 - (14 * (h+1)) as i32, coords.1, &self.get_asset_data(), target, sf)
             }
         }
 
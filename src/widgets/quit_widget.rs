@@ -1,8 +1,9 @@
 use std::collections::HashMap;
-use log::info;
 use sdl2::keyboard::Keycode::N;
 use sdl2::rect::Rect;
-use crate::game::{DyslexiaMode, Game};
+use crate::command::Command;
+use crate::context::Context;
+use crate::game::DyslexiaMode;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
 use crate::screen::Screen;
@@ -20,7 +21,7 @@ pub struct QuitWidget {
     asset_data_selected_dyslexia : AssetData,
     alignment: Alignment,
     coords : (i32, i32),
-    game : *mut Game
+    dyslexia_mode : DyslexiaMode
 }
 
 impl QuitWidget {
@@ -36,7 +37,7 @@ impl QuitWidget {
     }
      */
 
-    pub fn create(alignment: Alignment, x : i32, y : i32, game : *mut Game) -> Box<Self>
+    pub fn create(alignment: Alignment, x : i32, y : i32) -> Box<Self>
     where
         Self: Sized
     {
@@ -65,16 +66,19 @@ impl QuitWidget {
             },
             alignment,
             coords: (x, y),
-            game
+            dyslexia_mode : DyslexiaMode::OFF
         };
         Box::new(ret)
     }
 }
 
 impl Widget for QuitWidget {
-    fn on_click(&mut self) {
-        info!("Quitting game!");
-        unsafe{(*self.game).running =false;}
+    fn on_click(&mut self, ctx: &mut Context) {
+        ctx.push(Command::Quit);
+    }
+
+    fn tick(&mut self, ctx: &Context) {
+        self.dyslexia_mode = ctx.dyslexia_mode.clone();
     }
 
     fn get_selected(&mut self) -> bool {
@@ -95,7 +99,7 @@ impl Widget for QuitWidget {
 
     fn get_asset_data(&mut self) -> AssetData {
 
-        let dyslexia = unsafe { &mut *self.game }.dyslexia_mode.clone();
+        let dyslexia = self.dyslexia_mode.clone();
 
         match dyslexia {
             DyslexiaMode::ON => {
@@ -136,9 +140,6 @@ impl Widget for QuitWidget {
         self.alignment = alignment;
     }
 
-    fn get_game(&mut self) {
-        self.game;
-    }
 
 
 }
\ No newline at end of file
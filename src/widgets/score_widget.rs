@@ -1,9 +1,8 @@
-use std::collections::HashMap;
 use num::clamp;
 use sdl2::rect::Rect;
-use sdl2::render::{Texture, WindowCanvas};
+use crate::render_target::RenderTarget;
 use crate::entity::Entity;
-use crate::game::Game;
+use crate::context::Context;
 use crate::render;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
@@ -15,12 +14,11 @@ pub struct ScoreWidget {
     base_asset_data: AssetData,
     alignment: Alignment,
     coords : (i32, i32),
-    game : *mut Game,
     score : u32
 }
 
 impl ScoreWidget {
-    pub fn create(alignment: Alignment, x : i32, y : i32, game : *mut Game) -> Box<Self>
+    pub fn create(alignment: Alignment, x : i32, y : i32) -> Box<Self>
     where
         Self: Sized
     {
@@ -34,7 +32,6 @@ impl ScoreWidget {
             },
             alignment,
             coords: (x, y),
-            game,
             score: 0
         };
         Box::new(ret)
@@ -42,7 +39,11 @@ impl ScoreWidget {
 }
 
 impl Widget for ScoreWidget {
-    fn on_click(&mut self) {}
+    fn on_click(&mut self, ctx: &mut Context) {}
+
+    fn tick(&mut self, ctx: &Context) {
+        self.score = ctx.score as u32;
+    }
 
     fn get_selected(&mut self) -> bool {
         false
@@ -79,13 +80,9 @@ impl Widget for ScoreWidget {
         self.alignment = alignment
     }
 
-    // again why is this here what was I on??
-    fn get_game(&mut self) {
-    }
 
-    fn render(&mut self, textures: &HashMap<String, Texture>, sf: i32, canvas: &mut WindowCanvas, debug : bool) {
-        let game = unsafe { &mut *self.game };
-        let score_as_string = format!("{}",game.score.clone() as u32);
+    fn render(&mut self, sf : i32, target : &mut dyn RenderTarget, debug : bool) {
+        let score_as_string = format!("{}",self.score);
         let mut counter = 0;
         for character in score_as_string.chars() {
             let mut asset_data = self.base_asset_data.clone();
@@ -94,9 +91,9 @@ impl Widget for ScoreWidget {
                 let mut d_ass = asset_data.clone();
                 d_ass.uv = Some(Rect::new(0,0,d_ass.uv.unwrap().width(),d_ass.uv.unwrap().height()));
                 d_ass.resource_location = ResourceLocation::new("game", "gui/widgets/debug_background.png");;
-                render::draw_pp_texture(self.coords.0 + (8 * counter), self.coords.1, &d_ass, canvas, sf, textures);
+                render::draw_pp_texture(self.coords.0 + (8 * counter), self.coords.1, &d_ass, target, sf);
             }
-            render::draw_pp_texture(self.coords.0 + (8 * counter), self.coords.1, &asset_data, canvas, sf, textures);
+            render::draw_pp_texture(self.coords.0 + (8 * counter), self.coords.1, &asset_data, target, sf);
             counter+=1;
         }
 
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use sdl2::rect::Rect;
-use crate::game::Game;
+use crate::context::Context;
 use crate::render::AssetData;
 use crate::resource_location::ResourceLocation;
 use crate::widget::Alignment;
@@ -12,7 +12,6 @@ pub struct SourceWidget {
     asset_data_selected : AssetData,
     alignment: Alignment,
     coords : (i32, i32),
-    game : Option<*mut Game>
 }
 
 impl SourceWidget {
@@ -23,7 +22,6 @@ impl SourceWidget {
             asset_data_selected : AssetData::empty(),
             alignment : Alignment::NONE,
             coords : (0,0),
-            game: None,
         }
     }
 
@@ -40,7 +38,7 @@ impl SourceWidget {
 }
 
 impl SourceWidget {
-    pub fn create(alignment: Alignment, x : i32, y : i32, game : *mut Game) -> Box<Self>
+    pub fn create(alignment: Alignment, x : i32, y : i32) -> Box<Self>
     where
         Self: Sized
     {
@@ -59,14 +57,13 @@ impl SourceWidget {
             },
             alignment,
             coords: (x, y),
-            game : Some(game)
         };
         Box::new(ret)
     }
 }
 
 impl Widget for SourceWidget {
-    fn on_click(&mut self) {
+    fn on_click(&mut self, ctx: &mut Context) {
         let _ = open::that("https://github.com/BurritoBandit28/NEA-Project");
     }
 
@@ -111,9 +108,6 @@ impl Widget for SourceWidget {
         self.alignment = alignment;
     }
 
-    fn get_game(&mut self) {
-        self.game.unwrap();
-    }
 
 
 }
@@ -0,0 +1,89 @@
+use sdl2::rect::Rect;
+use crate::context::Context;
+use crate::render;
+use crate::render::{AssetData, FONT_SIZE};
+use crate::render_target::RenderTarget;
+use crate::resource_location::ResourceLocation;
+use crate::widget::{Alignment, Widget};
+
+/// A button made of a line of text, drawn with the pixel font. Saves drawing a texture for every button that only needs
+/// a word on it
+pub struct TextButtonWidget {
+    selected : bool,
+    text : String,
+    alignment: Alignment,
+    coords : (i32, i32),
+    click : fn(&mut Context)
+}
+
+impl TextButtonWidget {
+    pub fn create(text : &str, alignment: Alignment, x : i32, y : i32, click : fn(&mut Context)) -> Box<Self>
+    where
+        Self: Sized
+    {
+        Box::new(Self {
+            selected: false,
+            text: text.to_string(),
+            alignment,
+            coords: (x, y),
+            click
+        })
+    }
+}
+
+impl Widget for TextButtonWidget {
+    fn on_click(&mut self, ctx: &mut Context) {
+        (self.click)(ctx)
+    }
+
+    fn get_selected(&mut self) -> bool {
+        self.selected
+    }
+
+    fn set_selected(&mut self, tf: bool) {
+        self.selected = tf
+    }
+
+    fn get_screen_coordinates(&mut self) -> (i32, i32) {
+        self.coords
+    }
+
+    fn set_screen_coordinates(&mut self, x: i32, y: i32) {
+        self.coords = (x, y)
+    }
+
+    // only used for the size of the button, the text is drawn in render
+    fn get_asset_data(&mut self) -> AssetData {
+        AssetData {
+            uv: Some(Rect::new(0, 0, render::text_width(&self.text), FONT_SIZE.1)),
+            origin: (0, 0),
+            resource_location: ResourceLocation::new("game", "gui/widgets/debug_background.png"),
+        }
+    }
+
+    fn set_asset_data(&mut self, _ass: AssetData) {}
+
+    fn get_resource_location(&mut self) -> ResourceLocation {
+        ResourceLocation::new("game", "widgets/text_button")
+    }
+
+    fn get_allignment(&mut self) -> Alignment {
+        self.alignment.clone()
+    }
+
+    fn set_allignment(&mut self, alignment: Alignment) {
+        self.alignment = alignment
+    }
+
+    fn render(&mut self, sf: i32, target: &mut dyn RenderTarget, debug: bool) {
+        let coords = self.correct_coords();
+        if debug {
+            render::draw_pp_texture(coords.0, coords.1, &self.get_debug_asset_data(), target, sf)
+        }
+        // point at the button when the mouse is over it
+        if self.selected {
+            render::draw_text(coords.0 - FONT_SIZE.0 as i32, coords.1, ">", target, sf)
+        }
+        render::draw_text(coords.0, coords.1, &self.text, target, sf)
+    }
+}
@@ -0,0 +1,82 @@
+// Tests for the texture atlas, built on the CPU so they don't need a GPU.
+
+use image::{Rgba, RgbaImage};
+use sdl2::rect::Rect;
+use nea_project::atlas::{AtlasLayout, PAGE_SIZE};
+use nea_project::loader;
+use nea_project::resource_location::ResourceLocation;
+
+// an image filled with a single colour
+fn filled(w : u32, h : u32, colour : u8) -> RgbaImage {
+    RgbaImage::from_pixel(w, h, Rgba([colour, colour, colour, 255]))
+}
+
+#[test]
+fn atlas_pack_test() {
+    let mut images = vec![];
+    for i in 0..40 {
+        images.push((ResourceLocation::new("game", format!("small_{}.png", i).as_str()), filled(16 + i, 32, i as u8)));
+    }
+    images.push((ResourceLocation::new("game", "big.png"), filled(200, 300, 255)));
+
+    let layout = AtlasLayout::pack(images, 256);
+
+    // the small images share pages, the big one has its own
+    let big = layout.regions.get("game:big.png").unwrap();
+    assert_eq!(big.rect, Rect::new(0, 0, 200, 300));
+    assert_eq!(layout.pages[big.page].dimensions(), (200, 300));
+    assert!(layout.pages.len() < 40);
+
+    for i in 0..40 {
+        let region = layout.regions.get(&format!("game:small_{}.png", i)).unwrap();
+        assert_eq!((region.rect.width(), region.rect.height()), (16 + i, 32));
+
+        // every pixel was copied over
+        let page = &layout.pages[region.page];
+        assert_eq!(page.get_pixel(region.rect.x() as u32, region.rect.y() as u32), &Rgba([i as u8, i as u8, i as u8, 255]));
+        assert_eq!(page.get_pixel(region.rect.right() as u32 - 1, region.rect.bottom() as u32 - 1), &Rgba([i as u8, i as u8, i as u8, 255]));
+
+        // and nothing overlaps
+        for j in 0..i {
+            let other = layout.regions.get(&format!("game:small_{}.png", j)).unwrap();
+            let overlaps = other.rect.left() < region.rect.right() && region.rect.left() < other.rect.right()
+                && other.rect.top() < region.rect.bottom() && region.rect.top() < other.rect.bottom();
+            assert!(other.page != region.page || !overlaps);
+        }
+    }
+}
+
+#[test]
+fn atlas_remap_test() {
+    let layout = AtlasLayout::pack(vec![
+        (ResourceLocation::new("game", "a.png"), filled(32, 32, 0)),
+        (ResourceLocation::new("game", "b.png"), filled(32, 16, 0))
+    ], PAGE_SIZE);
+    let region = *layout.regions.get("game:b.png").unwrap();
+
+    // UVs are moved to where the image is on the page
+    let uv = region.remap(Some(Rect::new(16, 0, 16, 16))).unwrap();
+    assert_eq!(uv, Rect::new(region.rect.x() + 16, region.rect.y(), 16, 16));
+    assert_eq!(region.remap(None), Some(region.rect));
+
+    // anything outside of the image is clipped off instead of showing its neighbours
+    let uv = region.remap(Some(Rect::new(16, 8, 32, 32))).unwrap();
+    assert_eq!(uv, Rect::new(region.rect.x() + 16, region.rect.y() + 8, 16, 8));
+    assert_eq!(region.remap(Some(Rect::new(64, 64, 16, 16))), None);
+}
+
+#[test]
+fn pack_textures_test() {
+    let layout = loader::pack_textures("./assets/");
+
+    // the existing resource locations still work, and point to the same pixels
+    let region = layout.regions.get("game:entity/player.png").unwrap();
+    let original = image::open("./assets/game/entity/player.png").unwrap().to_rgba8();
+    let page = &layout.pages[region.page];
+    for (x, y, pixel) in original.enumerate_pixels() {
+        assert_eq!(page.get_pixel(region.rect.x() as u32 + x, region.rect.y() as u32 + y), pixel);
+    }
+
+    assert!(layout.regions.contains_key("game:missing.png"));
+    assert!(layout.pages.len() < layout.regions.len());
+}
@@ -0,0 +1,57 @@
+// Tests for collision between entities and the level's tiles.
+
+use nea_project::collision::{move_and_slide, Aabb};
+use nea_project::headless::{HeadlessRunner, ScriptedInput, DEFAULT_DELTA};
+use nea_project::level::Level;
+use nea_project::loader;
+use sdl2::keyboard::Scancode;
+
+#[test]
+fn aabb_test() {
+    let a = Aabb::new(0.0, 0.0, 16.0, 16.0);
+
+    assert!(a.intersects(&Aabb::new(8.0, 8.0, 16.0, 16.0)));
+    // touching edges don't count as overlapping
+    assert!(!a.intersects(&Aabb::new(16.0, 0.0, 16.0, 16.0)));
+    assert!(!a.intersects(&Aabb::new(0.0, -16.0, 16.0, 16.0)));
+
+    assert_eq!(Aabb::centred((8.0, 8.0), (4.0, 2.0)), Aabb::new(6.0, 7.0, 4.0, 2.0));
+    assert_eq!(a.union(&Aabb::new(20.0, -4.0, 4.0, 4.0)), Aabb::new(0.0, -4.0, 24.0, 20.0));
+}
+
+#[test]
+fn move_and_slide_test() {
+    let tiles = loader::load_tiles("./data/");
+    let level = Level::load("./data/game/levels/demo.json", &tiles).unwrap();
+
+    // nothing in the way
+    let hitbox = Aabb::centred((40.0, 40.0), (8.0, 8.0));
+    assert_eq!(move_and_slide(&level, hitbox, (5.0, -5.0)), (5.0, -5.0));
+
+    // the wall along the top of the room stops the hitbox at its edge, but it still slides to the right
+    let hitbox = Aabb::centred((40.0, 10.0), (8.0, 8.0));
+    assert_eq!(move_and_slide(&level, hitbox, (5.0, -20.0)), (5.0, -6.0));
+
+    // there is nothing to the right of the room, which is just as solid
+    let hitbox = Aabb::centred((250.0, 40.0), (8.0, 8.0));
+    assert_eq!(move_and_slide(&level, hitbox, (20.0, 0.0)), (2.0, 0.0));
+
+    // a hitbox already inside a wall can leave it
+    let hitbox = Aabb::centred((40.0, -2.0), (8.0, 8.0));
+    assert_eq!(move_and_slide(&level, hitbox, (0.0, 10.0)), (0.0, 10.0));
+}
+
+#[test]
+fn player_wall_test() {
+    let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    runner.load_demo_level();
+
+    // walk up and left into the corner of the room for long enough to go through the wall
+    runner.at(0, ScriptedInput::Hold(Scancode::W));
+    runner.at(0, ScriptedInput::Hold(Scancode::A));
+    runner.run_for(2.0);
+
+    // the player's hitbox is 8x8, so it stops 4 pixels from the wall and the edge of the room
+    let coords = runner.game.get_player().unwrap().lock().unwrap().get_coords();
+    assert_eq!(coords, (4.0, 4.0));
+}
@@ -0,0 +1,153 @@
+// Tests for entities, ticked directly with a context instead of through the game loop.
+
+use std::collections::HashMap;
+use nea_project::animation::{Animation, Animator};
+use nea_project::command::Command;
+use nea_project::entity_definition::Behaviour;
+use nea_project::game::Game;
+use nea_project::loader;
+use nea_project::resource_location::ResourceLocation;
+
+// a game instance with the entity definitions from the data directory, and a player
+fn create_game() -> Game {
+    let mut game = Game::initiate();
+    game.entity_definitions = loader::load_entities("./data/");
+    game.spawn(&ResourceLocation::new("game", "entity/player"), (0.0, 0.0));
+    game
+}
+
+#[test]
+fn load_entities_test() {
+    let definitions = loader::load_entities("./data/");
+
+    let turret = definitions.get("game:entity/turret").unwrap();
+    assert_eq!(turret.behaviour, Behaviour::Turret);
+    assert_eq!(turret.resource_location.to_string(), "game:entity/turret");
+    assert_eq!(turret.get_parameter("range", 0.0), 150.0);
+    // missing parameters fall back to the default
+    assert_eq!(turret.get_parameter("not_a_parameter", 3.0), 3.0);
+
+    let bomb = definitions.get("game:entity/floaty_bomb").unwrap();
+    assert_eq!(bomb.health, 12.0);
+    assert_eq!(bomb.hitbox, (16.0, 16.0));
+
+    // spawning something without a definition does nothing
+    let mut game = Game::initiate();
+    game.entity_definitions = definitions;
+    assert!(game.spawn(&ResourceLocation::new("game", "entity/not_an_entity"), (0.0, 0.0)).is_none());
+    assert!(game.entities.is_empty());
+}
+
+#[test]
+fn turret_tick_test() {
+    let mut game = create_game();
+    let player = game.player.unwrap();
+    let turret = game.spawn(&ResourceLocation::new("game", "entity/turret"), (0.0, 0.0)).unwrap();
+    let turret = game.entities.get(&turret).unwrap();
+
+    // the turret only shoots at a player below it
+    let _ = turret.lock().unwrap().set_coords((0.0, 0.0));
+    let _ = game.get_player().unwrap().lock().unwrap().set_coords((0.0, 40.0));
+
+    // the turret fires every half a second, but only asks the game to do anything about it
+    turret.lock().unwrap().physics(0.6, &mut game.context());
+    let commands = game.commands.take();
+    assert_eq!(commands.len(), 2);
+    assert!(matches!(commands[0], Command::ChangeHealth(uuid, amount) if uuid == player && amount == -1.0));
+    assert!(matches!(commands[1], Command::PlaySound(_)));
+    assert_eq!(game.get_player().unwrap().lock().unwrap().get_health(), 20.0);
+
+    // out of range
+    let _ = game.get_player().unwrap().lock().unwrap().set_coords((0.0, 400.0));
+    turret.lock().unwrap().physics(0.6, &mut game.context());
+    assert!(game.commands.is_empty());
+}
+
+#[test]
+fn floaty_bomb_collide_test() {
+    let mut game = create_game();
+    let player = game.player.unwrap();
+    let bomb_uuid = game.spawn(&ResourceLocation::new("game", "entity/floaty_bomb"), (0.0, 0.0)).unwrap();
+    let bomb = game.entities.get(&bomb_uuid).unwrap();
+
+    // touching the player makes it explode on its next tick, even without a level to follow
+    bomb.lock().unwrap().on_collide(player, &mut game.context());
+    bomb.lock().unwrap().tick(0.1, &mut game.context());
+
+    let commands = game.commands.take();
+    assert_eq!(commands.len(), 3);
+    assert!(matches!(commands[0], Command::Spawn(_, (0.0, 0.0))));
+    assert!(matches!(commands[1], Command::ChangeHealth(uuid, amount) if uuid == player && amount == -10.0));
+    assert!(matches!(commands[2], Command::Despawn(uuid) if uuid == bomb_uuid));
+}
+
+#[test]
+fn animator_test() {
+    let rl = ResourceLocation::new("game", "test.anim.json");
+    let blink = Animation::parse(rl.clone(), r#"{
+        "texture" : "game:test.png",
+        "frame_time" : 0.1,
+        "on_complete" : "done",
+        "frames" : [
+            { "x" : 0, "y" : 0, "w" : 16, "h" : 16 },
+            { "x" : 16, "y" : 0, "w" : 16, "h" : 16, "duration" : 0.3 }
+        ]
+    }"#).unwrap();
+    assert!(!blink.looping);
+    assert!((blink.duration() - 0.4).abs() < 0.001);
+
+    let mut walk = blink.clone();
+    walk.looping = true;
+
+    let mut animator = Animator::create(HashMap::from([
+        (String::from("blink"), blink),
+        (String::from("walk"), walk)
+    ]));
+    assert!(animator.get_asset_data().is_none());
+
+    // a one shot animation sends its event once, then stays on its last frame
+    animator.play("blink");
+    assert_eq!(animator.get_asset_data().unwrap().uv.unwrap().x(), 0);
+    assert_eq!(animator.tick(0.2), None);
+    assert_eq!(animator.get_asset_data().unwrap().uv.unwrap().x(), 16);
+    assert_eq!(animator.tick(0.3).as_deref(), Some("done"));
+    assert!(animator.is_finished());
+    assert_eq!(animator.tick(1.0), None);
+    assert_eq!(animator.get_asset_data().unwrap().uv.unwrap().x(), 16);
+
+    // a looping animation wraps back round to its first frame
+    animator.play("walk");
+    assert_eq!(animator.tick(0.45).as_deref(), Some("done"));
+    assert!(!animator.is_finished());
+    assert_eq!(animator.get_asset_data().unwrap().uv.unwrap().x(), 0);
+
+    // unknown animations are ignored
+    animator.play("not_an_animation");
+    assert_eq!(animator.current(), Some("walk"));
+
+    // missing frames are an error
+    assert!(Animation::parse(rl, r#"{ "texture" : "game:test.png", "frames" : [] }"#).is_err());
+}
+
+#[test]
+fn explosion_animation_test() {
+    let mut game = create_game();
+    game.animations = loader::load_animations("./assets/");
+    let explosion = game.spawn(&ResourceLocation::new("game", "entity/explosion"), (0.0, 0.0)).unwrap();
+    let explosion = game.entities.get(&explosion).unwrap();
+    game.commands.take();
+
+    // the camera shakes when it goes off
+    assert_eq!(game.camera.get_shake_strength(), 3.0);
+
+    // six frames stacked down the texture, an eighth of a second each
+    assert_eq!(explosion.lock().unwrap().get_asset_data().uv.unwrap().y(), 0);
+    explosion.lock().unwrap().tick(0.3, &mut game.context());
+    assert_eq!(explosion.lock().unwrap().get_asset_data().uv.unwrap().y(), 128);
+    assert!(game.commands.is_empty());
+
+    // it removes itself once the animation is over
+    explosion.lock().unwrap().tick(0.5, &mut game.context());
+    let commands = game.commands.take();
+    assert!(matches!(commands[..], [Command::Despawn(_)]));
+}
@@ -0,0 +1,266 @@
+// Tests for keybindings, rebinding them from the settings screen, and game controllers.
+
+use std::env;
+use sdl2::controller::{Axis, Button};
+use sdl2::event::Event;
+use sdl2::keyboard::{Mod, Scancode};
+use sdl2::mouse::MouseButton;
+use nea_project::gamepad::Gamepad;
+use nea_project::headless::{HeadlessRunner, ScriptedInput, DEFAULT_DELTA};
+use nea_project::input::{Action, Binding, Keybindings};
+use nea_project::screen::Screen;
+use nea_project::screens::main_menu_screen::MainMenuScreen;
+use nea_project::screens::room_editor_screen::RoomEditorScreen;
+use nea_project::screens::settings_screen::SettingsScreen;
+
+fn key_down(scancode : Scancode, keymod : Mod) -> Event {
+    Event::KeyDown { timestamp : 0, window_id : 0, keycode : None, scancode : Some(scancode), keymod, repeat : false }
+}
+
+fn axis(axis : Axis, value : i16) -> Event {
+    Event::ControllerAxisMotion { timestamp : 0, which : 0, axis, value }
+}
+
+fn button_down(button : Button) -> Event {
+    Event::ControllerButtonDown { timestamp : 0, which : 0, button }
+}
+
+// whether the widget at a column and row of the current screen is selected
+fn selected(runner : &mut HeadlessRunner, x : usize, y : usize) -> bool {
+    runner.game.current_screen.as_mut().unwrap().get_widgets()[y][x].get_selected()
+}
+
+#[test]
+fn keybindings_test() {
+    let keybindings = Keybindings::default();
+
+    // movement is held, with either hand
+    assert!(keybindings.is_held(Action::MoveUp, &[Scancode::W]));
+    assert!(keybindings.is_held(Action::MoveUp, &[Scancode::Up]));
+    assert!(!keybindings.is_held(Action::MoveUp, &[Scancode::S]));
+
+    // bindings with Ctrl need it held, and ones without don't mind
+    assert!(keybindings.is_pressed(Action::EditorUndo, &key_down(Scancode::Z, Mod::LCTRLMOD)));
+    assert!(!keybindings.is_pressed(Action::EditorUndo, &key_down(Scancode::Z, Mod::NOMOD)));
+    assert!(keybindings.is_pressed(Action::Quit, &key_down(Scancode::Escape, Mod::RCTRLMOD)));
+    assert!(!keybindings.is_pressed(Action::Quit, &Event::Quit { timestamp : 0 }));
+
+    // Ctrl on its own can't be bound
+    assert_eq!(Binding::from_event(&key_down(Scancode::LCtrl, Mod::LCTRLMOD)), None);
+
+    // names are written the same way they are read
+    assert_eq!(Binding::ctrl(Scancode::Z).get_name(), "Ctrl+Z");
+    assert_eq!(Binding::parse("ctrl+z"), Some(Binding::ctrl(Scancode::Z)));
+    assert_eq!(Binding::parse("Space"), Some(Binding::key(Scancode::Space)));
+    assert_eq!(Binding::parse("NotAKey"), None);
+    assert_eq!(Action::parse("editor_next_tile"), Some(Action::EditorNextTile));
+}
+
+#[test]
+fn keybindings_save_load_test() {
+    let mut keybindings = Keybindings::default();
+    keybindings.bind(Action::MoveUp, 0, Binding::key(Scancode::I));
+    keybindings.bind(Action::EditorSave, 1, Binding::ctrl(Scancode::K));
+
+    // only the key being replaced changes, and one past the end is added on
+    assert_eq!(keybindings.get(Action::MoveUp), &[Binding::key(Scancode::I), Binding::key(Scancode::Up)]);
+    assert_eq!(keybindings.get(Action::EditorSave), &[Binding::ctrl(Scancode::S), Binding::ctrl(Scancode::K)]);
+
+    let path = env::temp_dir().join("keybindings_save_load_test.json");
+    let path = path.to_str().unwrap();
+    keybindings.save(path).unwrap();
+    assert_eq!(Keybindings::load(path).unwrap(), keybindings);
+
+    // anything missing from the file keeps its default keys
+    std::fs::write(path, "{\"move_left\" : [\"J\", \"Left\"], \"jump\" : [\"Space\"]}").unwrap();
+    let loaded = Keybindings::load(path).unwrap();
+    assert_eq!(loaded.get(Action::MoveLeft), &[Binding::key(Scancode::J), Binding::key(Scancode::Left)]);
+    assert_eq!(loaded.get(Action::MoveUp), Keybindings::default().get(Action::MoveUp));
+}
+
+#[test]
+fn rebind_test() {
+    let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    runner.game.current_screen = Some(SettingsScreen::create(&mut runner.game));
+
+    // click on move up, then press I. Escape is bound rather than quitting the game
+    runner.at(0, ScriptedInput::MoveMouse(50, 22));
+    runner.at(1, ScriptedInput::Event(Event::MouseButtonDown { timestamp : 0, window_id : 0, which : 0, mouse_btn : MouseButton::Left, clicks : 1, x : 0, y : 0 }));
+    runner.at(2, ScriptedInput::Event(key_down(Scancode::LCtrl, Mod::LCTRLMOD)));
+    runner.at(3, ScriptedInput::Event(key_down(Scancode::Escape, Mod::NOMOD)));
+    runner.run_for(0.1);
+    assert!(runner.game.running);
+    assert_eq!(runner.game.keybindings.get(Action::MoveUp), &[Binding::key(Scancode::Escape), Binding::key(Scancode::Up)]);
+
+    // and again, this time with I
+    runner.at(10, ScriptedInput::Event(key_down(Scancode::Return, Mod::NOMOD)));
+    runner.at(11, ScriptedInput::Event(key_down(Scancode::I, Mod::NOMOD)));
+    runner.run_for(0.1);
+    assert_eq!(runner.game.keybindings.get(Action::MoveUp), &[Binding::key(Scancode::I), Binding::key(Scancode::Up)]);
+
+    // the player moves up with the new key, and not the one it replaced
+    runner.load_demo_level();
+    runner.at(12, ScriptedInput::Hold(Scancode::W));
+    runner.run_for(0.5);
+    let start = runner.game.get_player().unwrap().lock().unwrap().get_coords();
+    runner.at(42, ScriptedInput::Hold(Scancode::I));
+    runner.run_for(0.5);
+    let end = runner.game.get_player().unwrap().lock().unwrap().get_coords();
+    assert_eq!(start, (16.0, 80.0));
+    assert!(end.1 < start.1);
+}
+
+#[test]
+fn gamepad_test() {
+    let mut gamepad = Gamepad::default();
+
+    // resting slightly off centre doesn't move the player
+    assert_eq!(gamepad.update(&axis(Axis::LeftX, 4000)), None);
+    assert_eq!(gamepad.movement(), None);
+
+    // pushed all the way, the stick moves as fast as the keys
+    assert_eq!(gamepad.update(&axis(Axis::LeftX, i16::MAX)), Some((1, 0)));
+    assert_eq!(gamepad.movement(), Some((1.0, 0.0)));
+
+    // only pushing the stick past the threshold moves between widgets, not holding it there
+    assert_eq!(gamepad.update(&axis(Axis::LeftX, 30000)), None);
+    assert_eq!(gamepad.update(&axis(Axis::LeftY, i16::MIN)), Some((0, -1)));
+
+    assert_eq!(gamepad.update(&button_down(Button::DPadLeft)), Some((-1, 0)));
+    assert_eq!(gamepad.update(&button_down(Button::A)), None);
+    assert!(gamepad.is_held(Button::A));
+    assert!(Gamepad::is_pressed(Button::A, &button_down(Button::A)));
+
+    // unplugging the controller lets go of everything
+    gamepad.update(&Event::ControllerDeviceRemoved { timestamp : 0, which : 0 });
+    assert_eq!(gamepad, Gamepad::default());
+}
+
+#[test]
+fn gamepad_unplug_test() {
+    let mut gamepad = Gamepad::default();
+
+    // one controller walks right whilst another holds A
+    gamepad.update(&axis(Axis::LeftX, i16::MAX));
+    gamepad.update(&Event::ControllerButtonDown { timestamp : 0, which : 1, button : Button::A });
+    assert_eq!(gamepad.movement(), Some((1.0, 0.0)));
+    assert!(gamepad.is_held(Button::A));
+
+    // unplugging the second controller only lets go of its buttons
+    gamepad.update(&Event::ControllerDeviceRemoved { timestamp : 0, which : 1 });
+    assert_eq!(gamepad.movement(), Some((1.0, 0.0)));
+    assert!(!gamepad.is_held(Button::A));
+
+    // and then the first stops the player
+    gamepad.update(&Event::ControllerDeviceRemoved { timestamp : 0, which : 0 });
+    assert_eq!(gamepad.movement(), None);
+}
+
+#[test]
+fn gamepad_movement_test() {
+    // walk right with the keys, and then with the stick pushed all the way
+    let mut keys = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    keys.load_demo_level();
+    keys.at(0, ScriptedInput::Hold(Scancode::D));
+    keys.run_for(0.5);
+
+    let mut stick = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    stick.load_demo_level();
+    stick.at(0, ScriptedInput::Event(axis(Axis::LeftX, i16::MAX)));
+    stick.run_for(0.5);
+
+    let end = stick.game.get_player().unwrap().lock().unwrap().get_coords();
+    assert_eq!(end, keys.game.get_player().unwrap().lock().unwrap().get_coords());
+    assert!(end.0 > 16.0);
+
+    // letting go stops the player
+    stick.at(30, ScriptedInput::Event(axis(Axis::LeftX, 0)));
+    stick.run_for(0.5);
+    assert_eq!(stick.game.get_player().unwrap().lock().unwrap().get_coords(), end);
+}
+
+#[test]
+fn gamepad_navigation_test() {
+    let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    runner.game.current_screen = Some(MainMenuScreen::create(&mut runner.game));
+
+    // the first press picks the first widget, the editor, then down to the source button
+    runner.at(0, ScriptedInput::Event(button_down(Button::DPadDown)));
+    runner.at(1, ScriptedInput::Event(button_down(Button::DPadDown)));
+    runner.run_for(2.0 * DEFAULT_DELTA);
+    assert!(selected(&mut runner, 0, 1));
+    assert!(!selected(&mut runner, 0, 0));
+
+    // right to quit, and the stick works the same
+    runner.at(2, ScriptedInput::Event(axis(Axis::LeftX, i16::MAX)));
+    runner.at(3, ScriptedInput::Event(axis(Axis::LeftX, 0)));
+    runner.at(4, ScriptedInput::Event(axis(Axis::LeftY, i16::MAX)));
+    runner.run_for(3.0 * DEFAULT_DELTA);
+    assert!(selected(&mut runner, 1, 2));
+
+    // moving off the bottom stays on settings, which A presses
+    runner.at(5, ScriptedInput::Event(button_down(Button::DPadDown)));
+    runner.at(6, ScriptedInput::Event(button_down(Button::A)));
+    runner.run_for(2.0 * DEFAULT_DELTA);
+    assert_eq!(runner.game.current_screen.as_mut().unwrap().get_widgets().len(), Action::ALL.len() + 1);
+
+    // the settings screen starts on its first row, and moving the mouse hands selection back to it
+    runner.at(7, ScriptedInput::Event(button_down(Button::DPadUp)));
+    runner.run_for(DEFAULT_DELTA);
+    assert!(selected(&mut runner, 0, 0));
+    runner.at(8, ScriptedInput::MoveMouse(50, 162));
+    runner.run_for(DEFAULT_DELTA);
+    assert!(!selected(&mut runner, 0, 0));
+    assert!(selected(&mut runner, 0, Action::ALL.len()));
+}
+
+#[test]
+fn keyboard_navigation_test() {
+    let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    runner.game.current_screen = Some(MainMenuScreen::create(&mut runner.game));
+
+    // the arrow keys and the movement keys both move between widgets, and Tab goes through them in order
+    runner.at(0, ScriptedInput::Event(key_down(Scancode::Down, Mod::NOMOD)));
+    runner.at(1, ScriptedInput::Event(key_down(Scancode::Tab, Mod::NOMOD)));
+    runner.at(2, ScriptedInput::Event(key_down(Scancode::S, Mod::NOMOD)));
+    runner.run_for(3.0 * DEFAULT_DELTA);
+    assert!(selected(&mut runner, 1, 1));
+
+    // Tab goes back to the first widget after the last
+    runner.at(3, ScriptedInput::Event(key_down(Scancode::Tab, Mod::NOMOD)));
+    runner.at(4, ScriptedInput::Event(key_down(Scancode::Tab, Mod::NOMOD)));
+    runner.at(5, ScriptedInput::Event(key_down(Scancode::Tab, Mod::NOMOD)));
+    runner.run_for(3.0 * DEFAULT_DELTA);
+    assert!(selected(&mut runner, 0, 0));
+    assert!(!selected(&mut runner, 1, 2));
+
+    // over to play, down to quit and press it
+    runner.at(6, ScriptedInput::Event(key_down(Scancode::Right, Mod::NOMOD)));
+    runner.at(7, ScriptedInput::Event(key_down(Scancode::Down, Mod::NOMOD)));
+    runner.at(8, ScriptedInput::Event(key_down(Scancode::Return, Mod::NOMOD)));
+    runner.run_for(3.0 * DEFAULT_DELTA);
+    assert!(!runner.game.running);
+}
+
+#[test]
+fn focus_opt_out_test() {
+    // the editor uses the keys and the mouse itself, so nothing there takes focus
+    let path = env::temp_dir().join("focus_opt_out_test.json");
+    let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    runner.game.current_screen = Some(RoomEditorScreen::create_at(&mut runner.game, path.to_str().unwrap()));
+    runner.at(0, ScriptedInput::Event(key_down(Scancode::S, Mod::LCTRLMOD)));
+    runner.at(0, ScriptedInput::Event(key_down(Scancode::Down, Mod::NOMOD)));
+    runner.at(0, ScriptedInput::Event(button_down(Button::DPadDown)));
+    runner.step();
+    assert!(!selected(&mut runner, 0, 0));
+
+    // and shortcuts held with Ctrl don't move between widgets on the menus either
+    runner.game.current_screen = Some(MainMenuScreen::create(&mut runner.game));
+    runner.at(1, ScriptedInput::Event(key_down(Scancode::Down, Mod::LCTRLMOD)));
+    runner.at(1, ScriptedInput::Event(key_down(Scancode::Tab, Mod::RCTRLMOD)));
+    runner.step();
+    assert!(!selected(&mut runner, 0, 0));
+    runner.at(2, ScriptedInput::Event(key_down(Scancode::Down, Mod::NOMOD)));
+    runner.step();
+    assert!(selected(&mut runner, 0, 0));
+}
@@ -0,0 +1,219 @@
+// Integration tests that link against the game library, the same way external tools would.
+
+use nea_project::collision::Aabb;
+use nea_project::level::{Level, CHUNK_SIZE};
+use nea_project::loader;
+use nea_project::resource_location::ResourceLocation;
+use nea_project::tile::TileSize;
+
+#[test]
+fn load_tiles_test() {
+    let tiles = loader::load_tiles("./data/");
+
+    // every tile in the data directory is loaded and keyed by its resource location
+    assert!(tiles.contains_key(&ResourceLocation::new("game", "tiles/wall.json").to_string()));
+    assert!(tiles.contains_key(&ResourceLocation::new("game", "tiles/floor.json").to_string()));
+}
+
+#[test]
+fn demo_level_test() {
+    let tiles = loader::load_tiles("./data/");
+    let level = Level::load("./data/game/levels/demo.json", &tiles).unwrap();
+
+    // 4 walls along the top, 8x3 floor tiles
+    assert_eq!(level.tile_big.get_nodes().len(), 4);
+    assert_eq!(level.tile_medium.get_nodes().len(), 24);
+    assert!(level.tile_medium.get_tile_size() == TileSize::MEDIUM);
+
+    // the navigation graph covers the floor, 16x6 tiles
+    assert_eq!(level.tile_nav.get_nodes().len(), 96);
+
+    // a nav tile in the middle of the room is connected to all 8 of its neighbours, as the demo allows diagonals
+    assert_eq!(level.tile_nav.get_connections().get(&(5, 3)).unwrap().len(), 8);
+    // a nav tile in the corner can't cut through the wall, so only has 3
+    assert_eq!(level.tile_nav.get_connections().get(&(0, 0)).unwrap().len(), 3);
+
+    // the player and the entities are read from the file
+    assert_eq!(level.player_start, (16.0, 80.0));
+    assert_eq!(level.entity_spawns.len(), 2);
+    assert_eq!(level.entity_spawns[0].0.to_string(), "game:entity/turret");
+    assert_eq!(level.entity_spawns[0].1, (128.0, 36.0));
+}
+
+#[test]
+fn level_save_load_test() {
+    let tiles = loader::load_tiles("./data/");
+    let level = Level::load("./data/game/levels/demo.json", &tiles).unwrap();
+
+    let path = std::env::temp_dir().join("nea_project_level_save_load_test.json");
+    let path = path.to_str().unwrap();
+    level.save(path).unwrap();
+    let loaded = Level::load(path, &tiles).unwrap();
+
+    // every tile ends up in the same place with the same resource location
+    for (a, b) in [(&level.tile_big, &loaded.tile_big), (&level.tile_medium, &loaded.tile_medium), (&level.tile_small, &loaded.tile_small)] {
+        assert_eq!(a.get_nodes().len(), b.get_nodes().len());
+        for (coords, tile) in a.get_nodes() {
+            assert_eq!(tile.get_resource_location().to_string(), b.get_nodes().get(coords).unwrap().get_resource_location().to_string());
+        }
+    }
+    assert_eq!(loaded.tile_nav.get_nodes().len(), level.tile_nav.get_nodes().len());
+    assert_eq!(loaded.player_start, level.player_start);
+    assert_eq!(loaded.entity_spawns.len(), level.entity_spawns.len());
+
+    // saving the loaded level again gives the same file
+    let first = std::fs::read_to_string(path).unwrap();
+    loaded.save(path).unwrap();
+    assert_eq!(first, std::fs::read_to_string(path).unwrap());
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn missing_level_test() {
+    let tiles = loader::load_tiles("./data/");
+    assert!(Level::load("./data/game/levels/does_not_exist.json", &tiles).is_err());
+}
+
+#[test]
+fn build_nav_test() {
+    let tiles = loader::load_tiles("./data/");
+    let mut level = Level::create_empty();
+
+    // a 2x2 floor of medium tiles, 4x4 nav cells
+    for x in 0..2 {
+        for y in 0..2 {
+            level.tile_medium.append(tiles.get("game:tiles/floor.json").unwrap().clone(), (x, y), vec![]);
+        }
+    }
+    // a solid box in the middle of the floor, and another off the floor
+    level.tile_small.append(tiles.get("game:tiles/cardboard_box.json").unwrap().clone(), (1, 1), vec![]);
+    level.tile_small.append(tiles.get("game:tiles/cardboard_box.json").unwrap().clone(), (8, 8), vec![]);
+    level.build_nav();
+
+    // the box blocks one cell, and the box off the floor isn't walkable
+    assert_eq!(level.tile_nav.get_nodes().len(), 15);
+    assert!(level.tile_nav.get_nodes().get(&(1, 1)).is_none());
+    assert!(level.tile_nav.get_nodes().get(&(8, 8)).is_none());
+
+    // the cells next to the box aren't connected to it
+    assert_eq!(level.tile_nav.get_connections().get(&(1, 0)).unwrap().len(), 2);
+}
+
+#[test]
+fn path_to_test() {
+    let tiles = loader::load_tiles("./data/");
+    let mut level = Level::load("./data/game/levels/demo.json", &tiles).unwrap();
+
+    // across the room in world space, the path includes the start and the target tiles
+    let path = level.tile_nav.path_to(8, 8, 100, 40).unwrap();
+    assert_eq!(path.first(), Some(&(0, 0)));
+    assert_eq!(path.last(), Some(&(96, 32)));
+    // 6 tiles right and 2 tiles down is 6 steps, 2 of them diagonal
+    assert_eq!(path.len(), 7);
+    // the same length as the original implementation
+    assert_eq!(path.len(), level.tile_nav.path_to_linear_scan(8, 8, 100, 40).len());
+
+    // every step is to a neighbouring tile
+    for step in path.windows(2) {
+        assert_eq!((step[0].0 - step[1].0).abs().max((step[0].1 - step[1].1).abs()), 16);
+    }
+
+    // the target is in the wall, off the nav graph
+    assert!(level.tile_nav.path_to(8, 8, 8, -8).is_none());
+    // the start is off the nav graph
+    assert!(level.tile_nav.path_to(-100, 8, 8, 8).is_none());
+
+    // a floor tile that isn't connected to the room
+    level.tile_medium.append(tiles.get("game:tiles/floor.json").unwrap().clone(), (20, 20), vec![]);
+    level.build_nav();
+    assert!(level.tile_nav.path_to(8, 8, 20 * 32, 20 * 32).is_none());
+}
+
+#[test]
+fn weighted_path_test() {
+    let tiles = loader::load_tiles("./data/");
+    let mut level = Level::create_empty();
+    level.diagonal_nav = true;
+
+    // a 3x2 room of medium tiles, with the expensive orange tiles along the top row except at either end
+    for x in 0..3 {
+        level.tile_medium.append(tiles.get("game:tiles/floor.json").unwrap().clone(), (x, 1), vec![]);
+        let top = if x == 1 { "game:tiles/orange.json" } else { "game:tiles/floor.json" };
+        level.tile_medium.append(tiles.get(top).unwrap().clone(), (x, 0), vec![]);
+    }
+    level.build_nav();
+
+    // the orange tiles cost more to walk over
+    assert_eq!(level.tile_nav.get_nodes().get(&(2, 0)).unwrap().get_cost(), 3);
+    assert_eq!(level.tile_nav.get_connections().get(&(1, 0)).unwrap().iter().find(|c| c.0 == (2, 0)).unwrap().1, 30);
+
+    // going along the top row means going through the orange tiles, so the path goes around them through the bottom row
+    let path = level.tile_nav.path_to(8, 8, 5 * 16 + 8, 8).unwrap();
+    assert!(path.iter().any(|p| p.1 >= 32));
+    assert!(path.iter().all(|p| p.0 < 32 || p.0 >= 64 || p.1 >= 32));
+}
+
+#[test]
+fn flow_field_test() {
+    let tiles = loader::load_tiles("./data/");
+    let mut level = Level::load("./data/game/levels/demo.json", &tiles).unwrap();
+
+    // no flow field until there is something to follow
+    assert!(level.next_step((100.0, 8.0)).is_none());
+
+    // the player's starting tile
+    level.update_flow_field((16.0, 80.0));
+
+    // on the player's tile, the next step is the centre of that tile
+    assert_eq!(level.next_step((20.0, 84.0)), Some((24.0, 88.0)));
+
+    // following the flow field from the other side of the room reaches the player
+    let mut coords = (120.0, 8.0);
+    let mut steps = 0;
+    while level.tile_nav.to_tile_space(coords) != (1, 5) {
+        coords = level.next_step(coords).unwrap();
+        steps += 1;
+        assert!(steps < 16);
+    }
+    // 6 tiles left and 5 down, 5 of them diagonal
+    assert_eq!(steps, 6);
+
+    // a floor tile that isn't connected to the room
+    level.tile_medium.append(tiles.get("game:tiles/floor.json").unwrap().clone(), (20, 20), vec![]);
+    level.build_nav();
+    level.update_flow_field((16.0, 80.0));
+    assert!(level.next_step((20.0 * 32.0, 20.0 * 32.0)).is_none());
+}
+
+#[test]
+fn tiles_in_test() {
+    let tiles = loader::load_tiles("./data/");
+    let dirt = tiles.get("game:tiles/dirt.json").unwrap();
+    let mut level = Level::create_empty();
+
+    // a large map of 16x16 tiles, many chunks across, with negative coordinates
+    for x in -100..100 {
+        for y in -100..100 {
+            level.tile_small.append(dirt.clone(), (x, y), vec![]);
+        }
+    }
+
+    // only the tiles on screen are looked at, in order
+    let visible = level.tile_small.tiles_in(&Aabb::new(-8.0, -8.0, 320.0, 180.0));
+    assert_eq!(visible.len(), 21 * 12);
+    assert_eq!(visible.first().unwrap().0, (-1, -1));
+    assert_eq!(visible.last().unwrap().0, (19, 10));
+
+    // removed tiles aren't found any more, even on the edge of a chunk
+    level.tile_small.remove((0, 0));
+    level.tile_small.remove((CHUNK_SIZE - 1, 0));
+    let visible = level.tile_small.tiles_in(&Aabb::new(-8.0, -8.0, 320.0, 180.0));
+    assert_eq!(visible.len(), 21 * 12 - 2);
+    assert!(!visible.iter().any(|(coords, _)| *coords == (0, 0)));
+
+    // replacing a tile doesn't add it twice
+    level.tile_small.append(dirt.clone(), (1, 1), vec![]);
+    assert_eq!(level.tile_small.tiles_in(&Aabb::new(16.0, 16.0, 16.0, 16.0)).len(), 1);
+    assert!(level.tile_small.tiles_in(&Aabb::new(5000.0, 5000.0, 320.0, 180.0)).is_empty());
+}
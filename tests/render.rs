@@ -0,0 +1,161 @@
+// Tests for rendering, drawn into an image in memory so they can run without a window or GPU. Frames are compared against
+// the golden images in tests/golden/, which can be regenerated by running the tests with UPDATE_GOLDEN=1.
+
+use std::env;
+use image::{Rgba, RgbaImage};
+use sdl2::event::Event;
+use sdl2::keyboard::{Mod, Scancode};
+use sdl2::rect::Rect;
+use nea_project::atlas::{AtlasLayout, PAGE_SIZE};
+use nea_project::headless::{HeadlessRunner, ScriptedInput, DEFAULT_DELTA};
+use nea_project::loader;
+use nea_project::render_target::{RenderTarget, SoftwareTarget};
+use nea_project::resource_location::ResourceLocation;
+use nea_project::screen::Screen;
+use nea_project::screens::main_menu_screen::MainMenuScreen;
+use nea_project::screens::settings_screen::SettingsScreen;
+use nea_project::viewport::Viewport;
+
+// compare a frame against its golden image
+fn check_golden(name : &str, frame : &RgbaImage) {
+    let path = format!("./tests/golden/{}.png", name);
+    if env::var("UPDATE_GOLDEN").is_ok() {
+        frame.save(&path).unwrap();
+        return
+    }
+
+    let golden = image::open(&path).unwrap().to_rgba8();
+    if &golden != frame {
+        // save what was actually drawn to compare against
+        let actual = env::temp_dir().join(format!("{}.actual.png", name));
+        frame.save(&actual).unwrap();
+        panic!("{} doesn't match its golden image, the frame was saved to {}", name, actual.display());
+    }
+}
+
+#[test]
+fn software_target_test() {
+    let mut red = RgbaImage::from_pixel(2, 2, Rgba([255, 0, 0, 255]));
+    red.put_pixel(1, 1, Rgba([0, 0, 255, 128]));
+    let layout = AtlasLayout::pack(vec![(ResourceLocation::new("game", "red.png"), red)], PAGE_SIZE);
+    let region = *layout.regions.get("game:red.png").unwrap();
+
+    let mut target = SoftwareTarget::create((8, 8), &layout);
+    assert_eq!(target.get_dimensions(), (8, 8));
+
+    // drawn at double the size
+    target.set_scale(2);
+    target.copy(region.page, region.rect, Rect::new(1, 1, 2, 2));
+    assert_eq!(target.image.get_pixel(1, 1), &Rgba([0, 0, 0, 0]));
+    assert_eq!(target.image.get_pixel(2, 2), &Rgba([255, 0, 0, 255]));
+    assert_eq!(target.image.get_pixel(3, 3), &Rgba([255, 0, 0, 255]));
+
+    // half transparent pixels are blended with what is already there
+    assert_eq!(target.image.get_pixel(4, 4), &Rgba([0, 0, 128, 128]));
+    target.copy(region.page, region.rect, Rect::new(1, 1, 2, 2));
+    assert_eq!(target.image.get_pixel(4, 4), &Rgba([0, 0, 191, 191]));
+
+    // drawing off the edge is clipped
+    target.copy(region.page, region.rect, Rect::new(3, 3, 2, 2));
+    assert_eq!(target.image.get_pixel(7, 7), &Rgba([255, 0, 0, 255]));
+}
+
+#[test]
+fn viewport_test() {
+    // 16:9 fills the whole screen
+    let viewport = Viewport::fit((1920, 1080));
+    assert_eq!(viewport.sf, 6);
+    assert_eq!(viewport.rect, Rect::new(0, 0, 1920, 1080));
+
+    // 16:10 and ultrawide pick the scale that fits both ways, and centre the game
+    let viewport = Viewport::fit((1280, 800));
+    assert_eq!(viewport.sf, 4);
+    assert_eq!(viewport.rect, Rect::new(0, 40, 1280, 720));
+    let viewport = Viewport::fit((3440, 1440));
+    assert_eq!(viewport.sf, 8);
+    assert_eq!(viewport.rect, Rect::new(440, 0, 2560, 1440));
+    assert_eq!(viewport.get_dimensions(), (320, 180));
+
+    // the mouse is converted back to the game screen, and kept on it over the bars
+    assert_eq!(viewport.to_game((440, 0)), (0, 0));
+    assert_eq!(viewport.to_game((447, 15)), (0, 1));
+    assert_eq!(viewport.to_game(viewport.to_window((100, 50))), (100, 50));
+    assert_eq!(viewport.to_game((0, 0)), (0, 0));
+    assert_eq!(viewport.to_game((3439, 1439)), (319, 179));
+
+    // windows are sized to fit the game
+    let viewport = Viewport::windowed(3);
+    assert_eq!(viewport.window, (960, 540));
+    assert_eq!(viewport.rect, Rect::new(0, 0, 960, 540));
+}
+
+#[test]
+fn letterbox_test() {
+    let layout = loader::pack_textures("./assets/");
+    let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    runner.load_demo_level();
+    runner.step();
+
+    let frame = runner.render(&layout);
+    let viewport = Viewport::fit((1280, 800));
+    let letterboxed = runner.render_in(&layout, viewport);
+    assert_eq!(letterboxed.dimensions(), (1280, 800));
+
+    // the same frame scaled up, with nothing drawn over the bars
+    for (x, y, pixel) in letterboxed.enumerate_pixels() {
+        if y < 40 || y >= 760 {
+            assert_eq!(pixel, &Rgba([0, 0, 0, 0]));
+        }
+        else {
+            assert_eq!(pixel, frame.get_pixel(x / 4, (y - 40) / 4));
+        }
+    }
+}
+
+#[test]
+fn demo_level_golden_test() {
+    let layout = loader::pack_textures("./assets/");
+    let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    runner.load_demo_level();
+    runner.step();
+
+    // the level, its entities and the HUD
+    check_golden("demo_level", &runner.render(&layout));
+}
+
+#[test]
+fn main_menu_golden_test() {
+    let layout = loader::pack_textures("./assets/");
+    let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    runner.game.current_screen = Some(MainMenuScreen::create(&mut runner.game));
+    runner.step();
+
+    check_golden("main_menu", &runner.render(&layout));
+}
+
+#[test]
+fn main_menu_focus_golden_test() {
+    let layout = loader::pack_textures("./assets/");
+    let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    runner.game.current_screen = Some(MainMenuScreen::create(&mut runner.game));
+    // onto the editor, then across to play, without touching the mouse
+    runner.at(0, ScriptedInput::Event(Event::KeyDown { timestamp : 0, window_id : 0, keycode : None, scancode : Some(Scancode::Down), keymod : Mod::NOMOD, repeat : false }));
+    runner.at(1, ScriptedInput::Event(Event::KeyDown { timestamp : 0, window_id : 0, keycode : None, scancode : Some(Scancode::Right), keymod : Mod::NOMOD, repeat : false }));
+    runner.run_for(2.0 * DEFAULT_DELTA);
+
+    // play is drawn selected, the same as when the mouse is over it
+    check_golden("main_menu_focus", &runner.render(&layout));
+}
+
+#[test]
+fn settings_golden_test() {
+    let layout = loader::pack_textures("./assets/");
+    let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    runner.game.current_screen = Some(SettingsScreen::create(&mut runner.game));
+    // hovering over the first action
+    runner.at(0, ScriptedInput::MoveMouse(50, 22));
+    runner.step();
+
+    // every action and its keys, written in the pixel font
+    check_golden("settings", &runner.render(&layout));
+}
@@ -0,0 +1,85 @@
+// Tests for recording the input of a run and replaying it.
+
+use std::env;
+use sdl2::controller::{Axis, Button};
+use sdl2::event::Event;
+use sdl2::keyboard::{Mod, Scancode};
+use sdl2::mouse::{MouseButton, MouseWheelDirection};
+use nea_project::headless::{HeadlessRunner, ScriptedInput, DEFAULT_DELTA};
+use nea_project::replay::{Recording, TickInput};
+
+// where the player is, its health, the score and how many entities there are
+fn outcome(runner : &mut HeadlessRunner) -> (Option<(f32, f32)>, Option<f32>, f32, usize) {
+    let coords = runner.game.get_player().map(|p| p.lock().unwrap().get_coords());
+    (coords, runner.player_health(), runner.score(), runner.entity_count())
+}
+
+#[test]
+fn recording_save_load_test() {
+    let mut recording = Recording::new(DEFAULT_DELTA, Some("./data/game/levels/demo.json".to_string()));
+    recording.push(TickInput {
+        held_keys : vec![Scancode::W, Scancode::LShift],
+        events : vec![
+            Event::KeyDown { timestamp : 0, window_id : 0, keycode : None, scancode : Some(Scancode::Z), keymod : Mod::LCTRLMOD, repeat : false },
+            Event::MouseButtonDown { timestamp : 0, window_id : 0, which : 0, mouse_btn : MouseButton::Right, clicks : 1, x : 12, y : 40 },
+            Event::MouseWheel { timestamp : 0, window_id : 0, which : 0, x : 0, y : -1, direction : MouseWheelDirection::Normal, precise_x : 0.0, precise_y : -1.0 },
+            Event::ControllerAxisMotion { timestamp : 0, which : 0, axis : Axis::LeftY, value : -20000 },
+            Event::ControllerButtonDown { timestamp : 0, which : 0, button : Button::DPadRight },
+            Event::ControllerButtonUp { timestamp : 0, which : 0, button : Button::A },
+            Event::ControllerDeviceRemoved { timestamp : 0, which : 0 },
+            Event::Quit { timestamp : 0 }
+        ],
+        mouse : (160, 90)
+    });
+    recording.push(TickInput { held_keys : vec![], events : vec![], mouse : (0, 179) });
+
+    let path = env::temp_dir().join("recording_save_load_test.replay.json");
+    let path = path.to_str().unwrap();
+    recording.save(path).unwrap();
+    assert_eq!(Recording::load(path).unwrap(), recording);
+
+    assert!(Recording::load("./missing.replay.json").is_err());
+}
+
+#[test]
+fn record_replay_test() {
+    let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    runner.load_demo_level();
+    runner.start_recording(Some("./data/game/levels/demo.json".to_string()));
+
+    // wander around the room until the turret has been firing for a while
+    runner.at(0, ScriptedInput::Hold(Scancode::D));
+    runner.at_time(0.75, ScriptedInput::Hold(Scancode::W));
+    runner.at_time(1.5, ScriptedInput::Release(Scancode::D));
+    runner.at_time(2.0, ScriptedInput::MoveMouse(100, 60));
+    runner.at_time(2.5, ScriptedInput::Release(Scancode::W));
+    runner.run_for(3.0);
+    let recording = runner.take_recording().unwrap();
+    assert_eq!(recording.ticks.len(), 180);
+
+    // saved and replayed from the start, the run ends up in exactly the same place
+    let path = env::temp_dir().join("record_replay_test.replay.json");
+    let path = path.to_str().unwrap();
+    recording.save(path).unwrap();
+    let loaded = Recording::load(path).unwrap();
+    let mut replay = HeadlessRunner::from_recording("./data/", "./assets/", &loaded);
+    replay.replay(&loaded);
+
+    assert_eq!(replay.get_tick(), 180);
+    assert_eq!(outcome(&mut replay), outcome(&mut runner));
+}
+
+#[test]
+fn demo_level_replay_test() {
+    // a run through the demo level, walking right and then up whilst the turret and floaty bomb go for the player
+    let recording = Recording::load("./tests/replays/demo_level.replay.json").unwrap();
+    let mut runner = HeadlessRunner::from_recording("./data/", "./assets/", &recording);
+    runner.replay(&recording);
+
+    let (coords, health, score, entities) = outcome(&mut runner);
+    assert_eq!(coords, Some((76.0, 65.0)));
+    assert_eq!(health, Some(2.0));
+    assert!((score - 4.0).abs() < 0.01);
+    // the floaty bomb has gone off
+    assert_eq!(entities, 2);
+}
@@ -0,0 +1,164 @@
+// Tests for the spatial grid and the entity queries on Game and Context.
+
+use std::sync::{Arc, Mutex};
+use nea_project::collision::Aabb;
+use nea_project::context::Context;
+use nea_project::entity::Entity;
+use nea_project::headless::{HeadlessRunner, DEFAULT_DELTA};
+use nea_project::resource_location::ResourceLocation;
+use nea_project::spatial::{SpatialEntry, SpatialGrid, CELL_SIZE};
+use uuid::Uuid;
+
+// an entity that looks for everything near it each tick, through its context
+struct Sensor {
+    uuid : Uuid,
+    coords : (f32, f32),
+    resource_location : ResourceLocation,
+    found : Arc<Mutex<(Vec<Uuid>, Vec<Uuid>, Option<Uuid>)>>
+}
+
+impl Entity for Sensor {
+    fn tick(&mut self, _delta : f32, ctx : &mut Context) {
+        *self.found.lock().unwrap() = (
+            ctx.within_radius(self.coords, 30.0),
+            ctx.overlaps(&Aabb::new(16.0, 80.0, 0.0, 0.0)),
+            ctx.nearest_of_type(self.coords, &ResourceLocation::new("game", "entity/turret"))
+        );
+    }
+
+    fn get_coords(&mut self) -> (f32, f32) {
+        self.coords
+    }
+
+    fn set_coords(&mut self, coords : (f32, f32)) {
+        self.coords = coords
+    }
+
+    fn get_health(&mut self) -> f32 {
+        1.0
+    }
+
+    fn change_health(&mut self, _amount : f32) {}
+
+    fn get_resource_location(&self) -> &ResourceLocation {
+        &self.resource_location
+    }
+
+    fn get_uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn get_velocity(&mut self) -> (f32, f32) {
+        (0.0, 0.0)
+    }
+
+    fn set_velocity(&mut self, _velocity : (f32, f32)) {}
+}
+
+fn id(n : u128) -> Uuid {
+    Uuid::from_u128(n)
+}
+
+fn entry(n : u128, coords : (f32, f32), size : f32, rl : &str) -> SpatialEntry {
+    SpatialEntry {
+        uuid : id(n),
+        coords,
+        hitbox : Aabb::centred(coords, (size, size)),
+        resource_location : ResourceLocation::parse(rl.to_string())
+    }
+}
+
+#[test]
+fn spatial_grid_test() {
+    let mut grid = SpatialGrid::create(CELL_SIZE);
+    grid.insert(entry(0, (10.0, 10.0), 8.0, "game:entity/player"));
+    // spread over four cells
+    grid.insert(entry(1, (32.0, 32.0), 16.0, "game:entity/turret"));
+    grid.insert(entry(2, (38.0, 38.0), 8.0, "game:entity/floaty_bomb"));
+    grid.insert(entry(3, (200.0, 10.0), 8.0, "game:entity/turret"));
+
+    // a point inside the first entity
+    let found = grid.query(&Aabb::new(12.0, 12.0, 0.0, 0.0)).iter().map(|e| e.uuid).collect::<Vec<_>>();
+    assert_eq!(found, vec![id(0)]);
+
+    // an area covering the middle two
+    let found = grid.query(&Aabb::new(24.0, 24.0, 20.0, 20.0)).iter().map(|e| e.uuid).collect::<Vec<_>>();
+    assert_eq!(found, vec![id(1), id(2)]);
+
+    // by distance between coordinates, not hitboxes
+    let found = grid.within_radius((10.0, 10.0), 32.0).iter().map(|e| e.uuid).collect::<Vec<_>>();
+    assert_eq!(found, vec![id(0), id(1)]);
+
+    let turret = ResourceLocation::new("game", "entity/turret");
+    assert_eq!(grid.nearest_of_type((180.0, 0.0), &turret).unwrap().uuid, id(3));
+    assert_eq!(grid.nearest_of_type((0.0, 0.0), &turret).unwrap().uuid, id(1));
+    assert!(grid.nearest_of_type((0.0, 0.0), &ResourceLocation::new("game", "entity/enemy")).is_none());
+
+    // the overlapping pair shares four cells, but is only found once
+    assert_eq!(grid.overlapping_pairs(), vec![(id(1), id(2))]);
+
+    grid.clear();
+    assert!(grid.get_entries().is_empty());
+    assert!(grid.overlapping_pairs().is_empty());
+}
+
+#[test]
+fn game_queries_test() {
+    let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    runner.load_demo_level();
+    runner.step();
+
+    // the player is at (16, 80), the turret at (128, 36) and the floaty bomb at (86, 40)
+    let player = runner.game.player.unwrap();
+    let uuids = runner.game.entities.uuids();
+    let (turret, floaty_bomb) = (uuids[1], uuids[2]);
+    assert_eq!(runner.game.overlaps(&Aabb::new(16.0, 80.0, 0.0, 0.0)), vec![player]);
+    assert_eq!(runner.game.nearest_of_type((0.0, 0.0), &ResourceLocation::new("game", "entity/turret")), Some(turret));
+    assert_eq!(runner.game.within_radius((16.0, 80.0), 20.0), vec![player]);
+    assert_eq!(runner.game.within_radius((100.0, 40.0), 30.0), vec![turret, floaty_bomb]);
+}
+
+#[test]
+fn despawn_test() {
+    let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    runner.load_demo_level();
+    runner.step();
+
+    let uuids = runner.game.entities.uuids();
+    let (player, turret, floaty_bomb) = (uuids[0], uuids[1], uuids[2]);
+
+    // despawning is deferred until the end of the next cycle
+    runner.game.entities.despawn(turret);
+    assert!(!runner.game.entities.contains(&turret));
+    assert!(runner.game.entities.get(&turret).is_some());
+    runner.step();
+    assert!(runner.game.entities.get(&turret).is_none());
+
+    // the other entities can still be found by the same Uuid
+    assert_eq!(runner.game.entities.uuids(), vec![player, floaty_bomb]);
+    assert_eq!(runner.game.get_player().unwrap().lock().unwrap().get_uuid(), player);
+    assert_eq!(runner.game.entities.get(&floaty_bomb).unwrap().lock().unwrap().get_resource_location().to_string(), "game:entity/floaty_bomb");
+}
+
+#[test]
+fn context_queries_test() {
+    let mut runner = HeadlessRunner::create("./data/", "./assets/", DEFAULT_DELTA);
+    runner.load_demo_level();
+    let found = Arc::new(Mutex::new((vec![], vec![], None)));
+    let sensor = runner.game.entities.insert(Box::new(Mutex::new(Sensor {
+        uuid : id(1),
+        coords : (100.0, 40.0),
+        resource_location : ResourceLocation::new("test", "entity/sensor"),
+        found : found.clone()
+    })));
+    runner.step();
+
+    // entities can search for each other during their tick, finding themselves too
+    let player = runner.game.player.unwrap();
+    let uuids = runner.game.entities.uuids();
+    let (turret, floaty_bomb) = (uuids[1], uuids[2]);
+    let found = found.lock().unwrap();
+    assert_eq!(found.0, vec![turret, floaty_bomb, sensor]);
+    assert_eq!(found.1, vec![player]);
+    assert_eq!(found.2, Some(turret));
+}